@@ -2,14 +2,23 @@ mod model;
 mod ui;
 mod input;
 
-use crate::model::inventory::Inventory;
+use crate::model::affix;
+use crate::model::character_class::CharacterClass;
+use crate::model::experience::ExperienceCurve;
+use crate::model::loot_filter::LootFilter;
+use crate::model::loot_table::LootTable;
 use crate::model::item::{ArmourType, EquipmentType, WeaponType};
 use crate::model::modifier::{
     BasicStatModifier, CompositeStatModifier, FlatStatModifier, FrontStatModifier, ModifierKind,
     ModifierPass, ModifierTargetKind, RequirementModifier,
 };
 use crate::model::player::Player;
-use crate::model::stat::{Stat, StatBlock, StatType};
+use crate::model::profile::Profile;
+use crate::model::random::Rng;
+use crate::model::save::{self, SaveError};
+use crate::model::stash::Stash;
+use crate::model::vendor::Vendor;
+use crate::model::stat::StatType;
 use crate::ui::ratatui::state::player::PlayerState;
 use model::item::{Item, ItemClass, ItemRarity};
 use crossterm::execute;
@@ -21,18 +30,121 @@ use ratatui::backend::CrosstermBackend;
 use std::io;
 use std::io::{IsTerminal, stdout};
 use std::process::Command;
+use ui::console;
 use ui::ratatui::ratatui_app::RatatuiApp;
 
+/// A loot filter in the working directory replaces the built-in default.
+const LOOT_FILTER_PATH: &str = "loot.filter";
+/// Each character is saved to its own file in here, named after the character, and is
+/// loaded from it when present and saved back on exit.
+const CHARACTERS_DIR: &str = "characters";
+/// The character played when `--character <name>` does not pick another.
+const DEFAULT_CHARACTER: &str = "default";
+/// The profile, with the stash its characters share, is kept apart from any one character.
+const PROFILE_PATH: &str = "profile.save";
+
 fn main() -> Result<(), std::io::Error> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let character = match args.first().map(|x| x.as_str()) {
+        Some("--character") if args.len() > 1 => {
+            let name = args.remove(1);
+            args.remove(0);
+            name
+        }
+        Some("--character") => {
+            eprintln!("No character name given");
+            return Ok(())
+        }
+        _ => String::from(DEFAULT_CHARACTER),
+    };
+    let save_path = format!("{}/{}.save", CHARACTERS_DIR, character);
+
+    if !args.is_empty() {
+        let (player, profile) = match load_player(&save_path).and_then(|x| Ok((x, load_profile()?))) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("{}", e);
+                return Ok(())
+            }
+        };
+        if let Err(e) = console::run_command(player, &profile.stash, &args, &save_path) {
+            eprintln!("{}", e);
+        }
+        return Ok(())
+    }
+
     if !std::io::stdout().is_terminal() {
         spawn_terminal_and_exit();
         return Ok(())
     }
 
+    let loot_filter = if std::path::Path::new(LOOT_FILTER_PATH).exists() {
+        match LootFilter::load(LOOT_FILTER_PATH) {
+            Ok(loot_filter) => loot_filter,
+            Err(e) => {
+                eprintln!("{}", e);
+                return Ok(())
+            }
+        }
+    } else {
+        LootFilter::default()
+    };
+
+    let player = match load_player(&save_path) {
+        Ok(player) => player,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(())
+        }
+    };
+
+    let profile = match load_profile() {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(())
+        }
+    };
+
+    let loot_tables = ["Blacksmith", "Alchemist"].iter().filter_map(|x| LootTable::find(x)).collect();
+    let vendor = Vendor::new("Trader", loot_tables, player.level().max(1) as u32);
+    let mut app = RatatuiApp::new(PlayerState::from(player), profile.stash, vendor, loot_filter);
+
+    let mut terminal = init_terminal()?;    
+    app.run(&mut terminal)?;
+    restore_terminal()?;
+
+    if let Err(e) = save::save_player(app.player(), &save_path) {
+        eprintln!("{}", e);
+    }
+    if let Err(e) = save::save_profile(&Profile::new(profile.name, app.into_stash()), PROFILE_PATH) {
+        eprintln!("{}", e);
+    }
+    Ok(())
+}
+
+fn load_player(save_path: &str) -> Result<Player, SaveError> {
+    if std::path::Path::new(save_path).exists() {
+        save::load_player(save_path)
+    } else {
+        Ok(create_player())
+    }
+}
+
+fn load_profile() -> Result<Profile, SaveError> {
+    if std::path::Path::new(PROFILE_PATH).exists() {
+        save::load_profile(PROFILE_PATH)
+    } else {
+        Ok(Profile::new(String::from("Default"), Stash::with_tabs(&["Currency", "Gear", "Dump"])))
+    }
+}
+
+fn create_player() -> Player {
     let equippable_item = Item::builder()
         .base(String::from("Claymore"))
         .name(String::from("Big Long Sword"))
         .rarity(ItemRarity::Rare)
+        .class(ItemClass::Equipment(EquipmentType::Weapon(WeaponType::TwoHandSword)))
         .with_stat(StatType::MinimumDamage, 9)
         .with_stat(StatType::MaximumDamage, 17)
         .with_modifier(FlatStatModifier {
@@ -49,43 +161,28 @@ fn main() -> Result<(), std::io::Error> {
 
             target: ModifierTargetKind::Item,
         })
-        .with_modifier(FrontStatModifier {
-            front: StatType::IncreasedDamage,
-            stats: vec![StatType::MinimumDamage, StatType::MaximumDamage],
-            modifier_kind: ModifierKind::Percent,
-            modifier_pass: ModifierPass::Increased,
+        .with_modifier(FlatStatModifier {
             value: 10,
-            target: ModifierTargetKind::Character,
+            stat: StatType::MaximumDamage,
+            target: ModifierTargetKind::Item,
         })
         .build()
         .unwrap();
 
-    let mut player = Player {
-        base_stats: StatBlock {
-            stats: vec![
-                Stat::new(StatType::Strength, 15),
-                Stat::new(StatType::Dexterity, 13),
-                Stat::new(StatType::Intelligence, 8),
-                Stat::new(StatType::Level, 10),
-            ],
-        },
-        equippement: Inventory::new(),
-        inventory: Inventory::new(),
-    };
-
-    player.equip(equippable_item);
+    let curve = ExperienceCurve::default();
+    let mut player = Player::new(CharacterClass::find("Warrior").expect("Warrior is a class"));
+    player.gain_experience(curve.required_for(10), &curve);
 
-    let item = Item::builder()
-        .base(String::from("Hand Axe"))
-        .rarity(ItemRarity::Normal)
-        .class(ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Axe)))
-        .with_requirement(StatType::Strength, 15)
-        .with_requirement(StatType::Dexterity, 12)
-        .with_stat(StatType::MinimumDamage, 2)
-        .with_stat(StatType::MaximumDamage, 6)
-        .build();
+    for replaced in player.equip(equippable_item).unwrap() {
+        player.pickup(replaced).unwrap();
+    }
 
-    player.pickup(item.unwrap());
+    for base in ["Scale Vest", "Leather Boots"] {
+        let item = Item::builder().base(String::from(base)).build().unwrap();
+        player.equip(item).unwrap();
+    }
+    let item = Item::builder().base(String::from("Buckler")).build();
+    player.pickup(item.unwrap()).unwrap();
 
 
     let item = Item::builder()
@@ -95,19 +192,8 @@ fn main() -> Result<(), std::io::Error> {
         .class(ItemClass::Equipment(EquipmentType::Weapon(
             WeaponType::Sword,
         )))
-        .with_requirement(StatType::Strength, 1000)
         .with_stat(StatType::MinimumDamage, 3)
         .with_stat(StatType::MaximumDamage, 5)
-        .with_modifier(FlatStatModifier {
-            value: 5,
-            stat: StatType::Dexterity,
-            target: ModifierTargetKind::Item,
-        })
-        .with_modifier(FlatStatModifier {
-            value: 5,
-            stat: StatType::MinimumDamage,
-            target: ModifierTargetKind::Item,
-        })
         .with_modifier(FlatStatModifier {
             value: 10,
             stat: StatType::MaximumDamage,
@@ -121,10 +207,9 @@ fn main() -> Result<(), std::io::Error> {
             modifier_pass: ModifierPass::Increased,
             target: ModifierTargetKind::Item,
         })
-        .with_modifier(RequirementModifier { value: -98 })
         .build();
 
-    player.pickup(item.unwrap());
+    player.pickup(item.unwrap()).unwrap();
 
     let item = Item::builder()
         .base(String::from("Kris"))
@@ -138,20 +223,21 @@ fn main() -> Result<(), std::io::Error> {
         .with_stat(StatType::MinimumDamage, 1)
         .with_stat(StatType::MaximumDamage, 4)
         .with_modifier(BasicStatModifier {
-            value: 35,
+            value: 25,
             stat: StatType::IncreasedAttackSpeed,
             modifier_kind: ModifierKind::Percent,
             modifier_pass: ModifierPass::Increased,
-            target: ModifierTargetKind::Item,
+            target: ModifierTargetKind::Character,
         })
         .build();
 
-    player.pickup(item.unwrap());
+    player.pickup(item.unwrap()).unwrap();
 
     let item = Item::builder()
         .base(String::from("Shako"))
         .name(String::from("Harlequin's Crest"))
         .rarity(ItemRarity::Unique)
+        .quality(20)
         .class(ItemClass::Equipment(EquipmentType::Armour(
             ArmourType::Helmet,
         )))
@@ -168,7 +254,7 @@ fn main() -> Result<(), std::io::Error> {
             target: ModifierTargetKind::Character,
         })
         .build();
-    player.pickup(item.unwrap());
+    player.pickup(item.unwrap()).unwrap();
 
 
     let item = Item::builder()
@@ -179,22 +265,50 @@ fn main() -> Result<(), std::io::Error> {
         .with_modifier(RequirementModifier { value: -30 })
         .with_stat(StatType::Life, 40)
         .build();
-    player.pickup(item.unwrap());
+    player.pickup(item.unwrap()).unwrap();
 
-    // let ui = ConsoleUI::default();
-    //
-    // for item in items.iter() {
-    //     ui.display_item_view(&ItemView::from(item));
-    //     println!();
-    // }
-    //
-    // ui.display_player_view(&PlayerView::from(player));
+    let mut item = Item::builder()
+        .base(String::from("Leather Gloves"))
+        .rarity(ItemRarity::Rare)
+        .unidentified()
+        .build()
+        .unwrap();
+    affix::roll_affixes(&mut item, &mut Rng::new());
+    player.pickup(item).unwrap();
 
-    let mut app = RatatuiApp::new(PlayerState::from(player));
+    let item = Item::builder()
+        .base(String::from("Iron Ring"))
+        .build();
+    player.pickup(item.unwrap()).unwrap();
 
-    let mut terminal = init_terminal()?;    
-    app.run(&mut terminal)?;
-    restore_terminal()
+    for base in ["Orb of Alchemy", "Chaos Orb", "Blacksmith's Whetstone", "Armourer's Scrap"] {
+        let item = Item::builder()
+            .base(String::from(base))
+            .quantity(2)
+            .build();
+        player.pickup(item.unwrap()).unwrap();
+    }
+
+    for quantity in [25, 20] {
+        let item = Item::builder()
+            .base(String::from("Scroll of Wisdom"))
+            .quantity(quantity)
+            .build();
+        player.pickup(item.unwrap()).unwrap();
+    }
+
+    for (base, quantity) in [("Small Life Flask", 1), ("Health Potion", 5)] {
+        let item = Item::builder()
+            .base(String::from(base))
+            .quantity(quantity)
+            .build();
+        player.pickup(item.unwrap()).unwrap();
+    }
+
+    player.life = player.max_life() / 2;
+    player.gold = 200;
+    player.wear_equipment(15);
+    player
 }
 
 fn spawn_terminal_and_exit() {
@@ -236,3 +350,16 @@ fn restore_terminal() -> io::Result<()> {
     execute!(stdout(), LeaveAlternateScreen,)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::integrity;
+
+    #[test]
+    fn the_demo_character_passes_verification() {
+        let violations = integrity::check_player(&create_player(), &Stash::from_tabs(Vec::new()));
+        let messages: Vec<String> = violations.iter().map(|x| x.to_string()).collect();
+        assert!(messages.is_empty(), "{:#?}", messages);
+    }
+}
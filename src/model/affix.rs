@@ -0,0 +1,180 @@
+use std::ops::RangeInclusive;
+use std::sync::LazyLock;
+
+use crate::model::item::{ArmourType, EquipmentType, Item, ItemClass, ItemRarity};
+use crate::model::modifier::{
+    BasicStatModifier, FlatStatModifier, FrontStatModifier, Modifier, ModifierKind, ModifierPass,
+    ModifierTargetKind,
+};
+use crate::model::random::Rng;
+use crate::model::stat::StatType;
+
+const MAGIC_AFFIXES: RangeInclusive<i32> = 1..=2;
+const RARE_AFFIXES: RangeInclusive<i32> = 3..=6;
+
+const RARE_NAME_FIRST: &[&str] = &["Doom", "Grim", "Storm", "Blood", "Dusk", "Rune", "Bone", "Wrath"];
+const RARE_NAME_SECOND: &[&str] = &["Bite", "Song", "Veil", "Mark", "Shell", "Fang", "Ward", "Grasp"];
+
+/// The shape of modifier an affix rolls.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AffixKind {
+    Flat(StatType, ModifierTargetKind),
+    Percent(StatType, ModifierTargetKind),
+    /// Increased damage, which raises both minimum and maximum damage.
+    IncreasedDamage(ModifierTargetKind),
+    /// Increased defences, which raises armour, evasion and energy shield.
+    IncreasedDefences(ModifierTargetKind),
+}
+
+impl AffixKind {
+    pub fn build(&self, value: i32) -> Box<dyn Modifier> {
+        match *self {
+            AffixKind::Flat(stat, target) => Box::new(FlatStatModifier { value, stat, target }),
+            AffixKind::Percent(stat, target) => Box::new(BasicStatModifier {
+                value,
+                stat,
+                modifier_kind: ModifierKind::Percent,
+                modifier_pass: ModifierPass::Increased,
+                target,
+            }),
+            AffixKind::IncreasedDamage(target) => Box::new(FrontStatModifier {
+                front: StatType::IncreasedDamage,
+                value,
+                stats: vec![StatType::MinimumDamage, StatType::MaximumDamage],
+                modifier_kind: ModifierKind::Percent,
+                modifier_pass: ModifierPass::Increased,
+                target,
+            }),
+            AffixKind::IncreasedDefences(target) => Box::new(FrontStatModifier {
+                front: StatType::IncreasedDefences,
+                value,
+                stats: vec![StatType::Armour, StatType::Evasion, StatType::EnergyShield],
+                modifier_kind: ModifierKind::Percent,
+                modifier_pass: ModifierPass::Increased,
+                target,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Affix {
+    pub kind: AffixKind,
+    pub values: RangeInclusive<i32>,
+    pub weight: u32,
+    /// The equipment types that can roll this affix.
+    pub equipment: fn(&EquipmentType) -> bool,
+}
+
+impl Affix {
+    fn new(kind: AffixKind, values: RangeInclusive<i32>, weight: u32, equipment: fn(&EquipmentType) -> bool) -> Self {
+        Self {
+            kind,
+            values,
+            weight,
+            equipment,
+        }
+    }
+
+    /// Whether the item's class allows the affix. Flat affixes to the item's own stats also
+    /// need the item to have that stat, so armour only rolls the defences it has.
+    pub fn can_roll_on(&self, item: &Item) -> bool {
+        let allowed = match &item.item_class {
+            ItemClass::Equipment(equipment) => (self.equipment)(equipment),
+            _ => false,
+        };
+        match self.kind {
+            AffixKind::Flat(stat, ModifierTargetKind::Item) => allowed && item.base_stats.has(stat),
+            _ => allowed,
+        }
+    }
+
+    /// The affix a modifier could have been rolled from, matched by the stat it affects,
+    /// what it targets and its kind.
+    pub fn find_for(modifier: &dyn Modifier) -> Option<&'static Affix> {
+        AFFIXES.iter().find(|x| {
+            let sample = x.build(*x.values.start());
+            sample.target() == modifier.target()
+                && sample.get_affected_stat() == modifier.get_affected_stat()
+                && sample.kind() == modifier.kind()
+        })
+    }
+
+    pub fn build(&self, value: i32) -> Box<dyn Modifier> {
+        self.kind.build(value)
+    }
+
+    pub fn roll(&self, rng: &mut Rng) -> Box<dyn Modifier> {
+        self.build(rng.range(self.values.clone()))
+    }
+}
+
+/// Replaces the item's explicit modifiers with new ones for its rarity: none for normal
+/// items, one or two for magic items and three to six for rare items, each from a different
+/// affix. Rare items are also given a new name. Uniques keep their fixed modifiers, and
+/// implicit modifiers are never touched.
+pub fn roll_affixes(item: &mut Item, rng: &mut Rng) {
+    let count = match item.rarity {
+        ItemRarity::Normal => 0,
+        ItemRarity::Magic => rng.range(MAGIC_AFFIXES),
+        ItemRarity::Rare => rng.range(RARE_AFFIXES),
+        ItemRarity::Unique => return,
+    };
+
+    let mut pool: Vec<&Affix> = AFFIXES.iter().filter(|x| x.can_roll_on(item)).collect();
+    item.modifiers.clear();
+    for _ in 0..count {
+        let Some(affix) = rng.weighted(&pool, |x| x.weight).copied() else {
+            break;
+        };
+        pool.retain(|x| x.kind != affix.kind);
+        item.modifiers.push(affix.roll(rng));
+    }
+
+    if item.rarity == ItemRarity::Rare {
+        let first = RARE_NAME_FIRST[rng.range(0..=RARE_NAME_FIRST.len() as i32 - 1) as usize];
+        let second = RARE_NAME_SECOND[rng.range(0..=RARE_NAME_SECOND.len() as i32 - 1) as usize];
+        item.name = Some(format!("{} {}", first, second));
+    }
+}
+
+fn any(_: &EquipmentType) -> bool {
+    true
+}
+
+fn weapon(equipment: &EquipmentType) -> bool {
+    matches!(equipment, EquipmentType::Weapon(_))
+}
+
+fn armour(equipment: &EquipmentType) -> bool {
+    matches!(equipment, EquipmentType::Armour(_))
+}
+
+fn shield(equipment: &EquipmentType) -> bool {
+    matches!(equipment, EquipmentType::Armour(ArmourType::Shield))
+}
+
+fn not_weapon(equipment: &EquipmentType) -> bool {
+    !weapon(equipment)
+}
+
+static AFFIXES: LazyLock<Vec<Affix>> = LazyLock::new(|| {
+    use AffixKind::*;
+    use ModifierTargetKind::{Character, Item};
+
+    vec![
+        Affix::new(Flat(StatType::Strength, Character), 5..=30, 100, any),
+        Affix::new(Flat(StatType::Dexterity, Character), 5..=30, 100, any),
+        Affix::new(Flat(StatType::Intelligence, Character), 5..=30, 100, any),
+        Affix::new(Flat(StatType::Life, Character), 10..=80, 100, not_weapon),
+        Affix::new(Percent(StatType::IncreasedAttackSpeed, Character), 5..=25, 60, weapon),
+        Affix::new(IncreasedDamage(Item), 10..=120, 80, weapon),
+        Affix::new(Flat(StatType::MaximumDamage, Item), 2..=15, 80, weapon),
+        Affix::new(Flat(StatType::CriticalChance, Item), 1..=5, 40, weapon),
+        Affix::new(Flat(StatType::Armour, Item), 5..=40, 80, armour),
+        Affix::new(Flat(StatType::Evasion, Item), 5..=40, 80, armour),
+        Affix::new(Flat(StatType::EnergyShield, Item), 3..=25, 80, armour),
+        Affix::new(IncreasedDefences(Item), 10..=80, 60, armour),
+        Affix::new(Flat(StatType::BlockChance, Item), 2..=8, 30, shield),
+    ]
+});
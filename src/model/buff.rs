@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use crate::model::stat::StatType;
+
+/// How a buff combines with an active buff of the same name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StackingRule {
+    /// Reapplying restarts the duration.
+    Refresh,
+    /// Reapplying adds a stack, up to the buff's maximum, and restarts the duration.
+    Stack,
+    /// Only the buff with the largest effect is kept.
+    Strongest,
+}
+
+/// A timed change to one of the player's stats. Buffs with a negative value are debuffs.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Buff {
+    pub name: &'static str,
+    pub stat: StatType,
+    pub value: i32,
+    pub duration: Duration,
+    pub stacking: StackingRule,
+    pub max_stacks: u32,
+}
+
+impl Buff {
+    pub fn new(name: &'static str, stat: StatType, value: i32, duration: Duration, stacking: StackingRule) -> Self {
+        Self {
+            name,
+            stat,
+            value,
+            duration,
+            stacking,
+            max_stacks: 1,
+        }
+    }
+
+    pub fn stacking_to(mut self, max_stacks: u32) -> Self {
+        self.max_stacks = max_stacks;
+        self
+    }
+
+    pub fn is_debuff(&self) -> bool {
+        self.value < 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ActiveEffect {
+    pub buff: Buff,
+    pub stacks: u32,
+    pub remaining: Duration,
+}
+
+impl ActiveEffect {
+    pub fn value(&self) -> i32 {
+        self.buff.value * self.stacks as i32
+    }
+}
+
+/// Adds a buff to the active effects, combining it with an active buff of the same name
+/// according to the new buff's stacking rule.
+pub(super) fn apply(effects: &mut Vec<ActiveEffect>, buff: Buff) {
+    let Some(active) = effects.iter_mut().find(|x| x.buff.name == buff.name) else {
+        effects.push(ActiveEffect {
+            buff,
+            stacks: 1,
+            remaining: buff.duration,
+        });
+        return;
+    };
+
+    match buff.stacking {
+        StackingRule::Refresh => {
+            active.buff = buff;
+            active.remaining = buff.duration;
+        }
+        StackingRule::Stack => {
+            active.buff = buff;
+            active.stacks = (active.stacks + 1).min(buff.max_stacks);
+            active.remaining = buff.duration;
+        }
+        StackingRule::Strongest => {
+            if buff.value.abs() >= active.value().abs() {
+                *active = ActiveEffect {
+                    buff,
+                    stacks: 1,
+                    remaining: buff.duration,
+                };
+            }
+        }
+    }
+}
+
+/// Counts down every effect and removes those that have run out.
+pub(super) fn tick(effects: &mut Vec<ActiveEffect>, elapsed: Duration) {
+    for effect in effects.iter_mut() {
+        effect.remaining = effect.remaining.saturating_sub(elapsed);
+    }
+    effects.retain(|x| !x.remaining.is_zero());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buff(value: i32, stacking: StackingRule) -> Buff {
+        Buff::new("Might", StatType::Strength, value, Duration::from_secs(10), stacking).stacking_to(3)
+    }
+
+    #[test]
+    fn refreshing_restarts_the_duration() {
+        let mut effects = Vec::new();
+        apply(&mut effects, buff(5, StackingRule::Refresh));
+        tick(&mut effects, Duration::from_secs(4));
+        apply(&mut effects, buff(5, StackingRule::Refresh));
+
+        assert_eq!(effects.len(), 1);
+        assert_eq!((effects[0].stacks, effects[0].remaining), (1, Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn stacks_add_up_to_the_maximum() {
+        let mut effects = Vec::new();
+        for _ in 0..5 {
+            apply(&mut effects, buff(5, StackingRule::Stack));
+        }
+        assert_eq!(effects[0].stacks, 3);
+        assert_eq!(effects[0].value(), 15);
+    }
+
+    #[test]
+    fn only_the_strongest_effect_is_kept() {
+        let mut effects = Vec::new();
+        apply(&mut effects, buff(-8, StackingRule::Strongest));
+        apply(&mut effects, buff(5, StackingRule::Strongest));
+        assert_eq!(effects[0].value(), -8);
+
+        apply(&mut effects, buff(10, StackingRule::Strongest));
+        assert_eq!(effects[0].value(), 10);
+    }
+
+    #[test]
+    fn effects_run_out() {
+        let mut effects = Vec::new();
+        apply(&mut effects, buff(5, StackingRule::Refresh));
+        apply(&mut effects, Buff::new("Rage", StatType::IncreasedAttackSpeed, 5, Duration::from_secs(20), StackingRule::Stack));
+
+        tick(&mut effects, Duration::from_secs(10));
+        let names: Vec<&str> = effects.iter().map(|x| x.buff.name).collect();
+        assert_eq!(names, ["Rage"]);
+    }
+}
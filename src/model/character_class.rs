@@ -0,0 +1,129 @@
+use std::sync::LazyLock;
+
+use crate::model::item::Item;
+use crate::model::stat::{Stat, StatBlock, StatType};
+
+/// An item every new character of a class begins with. Equipment is equipped, everything
+/// else goes into the inventory.
+#[derive(Debug, Clone)]
+pub struct StartingItem {
+    pub base: &'static str,
+    pub quantity: u32,
+}
+
+impl StartingItem {
+    fn new(base: &'static str) -> Self {
+        Self {
+            base,
+            quantity: 1,
+        }
+    }
+
+    fn quantity(mut self, quantity: u32) -> Self {
+        self.quantity = quantity;
+        self
+    }
+
+    pub fn build(&self) -> Item {
+        Item::builder()
+            .base(String::from(self.base))
+            .quantity(self.quantity)
+            .build()
+            .expect("starting items use catalog bases")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CharacterClass {
+    pub name: &'static str,
+    /// Stats of a new level 1 character.
+    pub base_stats: Vec<(StatType, i32)>,
+    /// Stats gained on every level up, on top of attribute points.
+    pub growth: Vec<(StatType, i32)>,
+    pub starting_items: Vec<StartingItem>,
+}
+
+impl CharacterClass {
+    pub fn find(name: &str) -> Option<&'static CharacterClass> {
+        CLASSES.iter().find(|x| x.name == name)
+    }
+
+    pub fn all() -> &'static [CharacterClass] {
+        &CLASSES
+    }
+
+    pub fn starting_stats(&self) -> StatBlock {
+        let mut stats = StatBlock::default();
+        stats.add(Stat::new(StatType::Level, 1));
+        for (stat_type, value) in self.base_stats.iter() {
+            stats.add(Stat::new(*stat_type, *value));
+        }
+        stats
+    }
+}
+
+static CLASSES: LazyLock<Vec<CharacterClass>> = LazyLock::new(|| {
+    vec![
+        CharacterClass {
+            name: "Warrior",
+            base_stats: vec![
+                (StatType::Strength, 14),
+                (StatType::Dexterity, 8),
+                (StatType::Intelligence, 5),
+                (StatType::Life, 50),
+            ],
+            growth: vec![(StatType::Strength, 2), (StatType::Dexterity, 1), (StatType::Life, 6)],
+            starting_items: vec![
+                StartingItem::new("Hand Axe"),
+                StartingItem::new("Health Potion").quantity(3),
+            ],
+        },
+        CharacterClass {
+            name: "Ranger",
+            base_stats: vec![
+                (StatType::Strength, 8),
+                (StatType::Dexterity, 14),
+                (StatType::Intelligence, 6),
+                (StatType::Life, 42),
+            ],
+            growth: vec![(StatType::Dexterity, 2), (StatType::Strength, 1), (StatType::Life, 5)],
+            starting_items: vec![
+                StartingItem::new("Kris"),
+                StartingItem::new("Health Potion").quantity(3),
+            ],
+        },
+        CharacterClass {
+            name: "Sorcerer",
+            base_stats: vec![
+                (StatType::Strength, 5),
+                (StatType::Dexterity, 7),
+                (StatType::Intelligence, 15),
+                (StatType::Life, 38),
+            ],
+            growth: vec![(StatType::Intelligence, 2), (StatType::Dexterity, 1), (StatType::Life, 4)],
+            starting_items: vec![
+                StartingItem::new("Sage's Circlet"),
+                StartingItem::new("Health Potion").quantity(3),
+            ],
+        },
+    ]
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::equipment::EquipmentSlot;
+    use crate::model::player::Player;
+
+    #[test]
+    fn every_class_starts_with_its_gear_on() {
+        for class in CharacterClass::all() {
+            let player = Player::new(class);
+            let carried = player.equippement.iter().count() + player.inventory.iter().count();
+            assert_eq!(carried, class.starting_items.len(), "{}", class.name);
+            assert!(player.inventory.iter().all(|x| EquipmentSlot::for_class(&x.item_class).is_none()), "{}", class.name);
+            assert!(player.equippement.iter().all(|x| player.can_use(x)), "{}", class.name);
+            assert_eq!(player.life, player.max_life());
+        }
+    }
+}
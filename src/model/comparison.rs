@@ -0,0 +1,82 @@
+use crate::model::equipment::EquipmentSlot;
+use crate::model::requirement::StatRequirement;
+use crate::model::stat::{StatBlock, StatType};
+
+#[derive(Debug, Clone)]
+pub struct StatDelta {
+    pub stat_type: StatType,
+    pub before: i32,
+    pub after: i32,
+}
+
+impl StatDelta {
+    pub fn difference(&self) -> i32 {
+        self.after - self.before
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequirementChange {
+    pub item_name: String,
+    pub requirement: StatRequirement,
+    pub is_met: bool,
+}
+
+#[derive(Debug)]
+pub struct EquipmentComparison {
+    pub slot: EquipmentSlot,
+    /// Names of the equipped items the candidate would take off, two for a two-handed
+    /// weapon replacing a weapon and a shield.
+    pub replaced: Vec<String>,
+    pub stats: Vec<StatDelta>,
+    pub requirements: Vec<RequirementChange>,
+}
+
+impl EquipmentComparison {
+    pub(super) fn stat_deltas(before: &StatBlock, after: &StatBlock) -> Vec<StatDelta> {
+        let mut stat_types: Vec<StatType> = before.stats.iter().map(|s| s.stat_type).collect();
+        for stat in after.stats.iter() {
+            if !stat_types.contains(&stat.stat_type) {
+                stat_types.push(stat.stat_type);
+            }
+        }
+
+        stat_types
+            .into_iter()
+            .map(|stat_type| StatDelta {
+                stat_type,
+                before: before.get(stat_type).map(|s| s.value).unwrap_or(0),
+                after: after.get(stat_type).map(|s| s.value).unwrap_or(0),
+            })
+            .collect()
+    }
+
+    pub fn changed_stats(&self) -> impl Iterator<Item = &StatDelta> {
+        self.stats.iter().filter(|d| d.difference() != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::stat::Stat;
+
+    fn block(stats: &[(StatType, i32)]) -> StatBlock {
+        let mut block = StatBlock::default();
+        for (stat_type, value) in stats.iter() {
+            block.add(Stat::new(*stat_type, *value));
+        }
+        block
+    }
+
+    #[test]
+    fn stats_missing_on_one_side_count_as_zero() {
+        let before = block(&[(StatType::Strength, 10), (StatType::Armour, 5)]);
+        let after = block(&[(StatType::Strength, 10), (StatType::Evasion, 8)]);
+
+        let deltas = EquipmentComparison::stat_deltas(&before, &after);
+        let changed: Vec<(StatType, i32)> = deltas.iter().filter(|x| x.difference() != 0).map(|x| (x.stat_type, x.difference())).collect();
+        assert_eq!(changed, [(StatType::Armour, -5), (StatType::Evasion, 8)]);
+        assert_eq!(deltas.len(), 3);
+    }
+}
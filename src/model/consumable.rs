@@ -0,0 +1,98 @@
+use std::fmt::{Display, Formatter};
+
+use crate::model::buff::Buff;
+use crate::model::player::Player;
+use crate::model::stat::{Stat, StatType};
+
+/// What happens to the player when a consumable is used.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UseEffect {
+    RestoreLife(i32),
+    /// Permanently raises one of the player's base stats.
+    GainStat(StatType, i32),
+    ApplyBuff(Buff),
+}
+
+impl UseEffect {
+    pub fn apply(&self, player: &mut Player) {
+        match *self {
+            UseEffect::RestoreLife(amount) => {
+                // Healing never takes away life above a maximum lowered by a gear change.
+                player.life = (player.life + amount).min(player.max_life()).max(player.life);
+            }
+            UseEffect::GainStat(stat_type, amount) => match player.base_stats.get_mut(stat_type) {
+                Some(stat) => stat.value += amount,
+                None => player.base_stats.add(Stat::new(stat_type, amount)),
+            },
+            UseEffect::ApplyBuff(buff) => player.apply_buff(buff),
+        }
+    }
+}
+
+impl Display for UseEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UseEffect::RestoreLife(amount) => write!(f, "Restores {} Life", amount),
+            UseEffect::GainStat(stat_type, amount) => write!(f, "Permanently grants +{} {}", amount, stat_type),
+            UseEffect::ApplyBuff(buff) => write!(
+                f,
+                "Grants {:+} {} for {} seconds",
+                buff.value,
+                buff.stat,
+                buff.duration.as_secs()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::character_class::CharacterClass;
+    use crate::model::item_builder::item;
+    use uuid::Uuid;
+
+    fn warrior() -> Player {
+        Player::new(CharacterClass::find("Warrior").unwrap())
+    }
+
+    fn pick_up(player: &mut Player, base: &str) -> Uuid {
+        let item = item(base);
+        let id = item.id;
+        player.pickup(item).unwrap();
+        id
+    }
+
+    #[test]
+    fn potions_heal_up_to_maximum_life_and_are_used_up() {
+        let mut player = warrior();
+        let potions = player.inventory.iter().find(|x| x.item_base == "Health Potion").unwrap().id;
+        player.life = player.max_life() - 5;
+
+        assert!(player.use_item(potions));
+        assert_eq!(player.life, player.max_life());
+        assert_eq!(player.inventory.iter().find(|x| x.id == potions).map(|x| x.quantity), Some(2));
+    }
+
+    #[test]
+    fn flasks_spend_charges_and_stay_when_empty() {
+        let mut player = warrior();
+        let flask = pick_up(&mut player, "Small Life Flask");
+
+        for _ in 0..3 {
+            assert!(player.use_item(flask));
+        }
+        assert!(!player.use_item(flask));
+        assert_eq!(player.inventory.iter().find(|x| x.id == flask).and_then(|x| x.charges), Some(0));
+    }
+
+    #[test]
+    fn tomes_raise_a_base_stat_for_good() {
+        let mut player = warrior();
+        let tome = pick_up(&mut player, "Tome of Strength");
+
+        assert!(player.use_item(tome));
+        assert_eq!(player.base_stats.get(StatType::Strength).map(|x| x.value), Some(15));
+        assert!(player.inventory.iter().all(|x| x.id != tome));
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::{Display, Formatter};
+
+use crate::model::affix;
+use crate::model::item::{EquipmentType, Item, ItemClass, ItemRarity, MAX_QUALITY};
+use crate::model::random::Rng;
+
+/// What a currency item does to the item it is applied to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CurrencyEffect {
+    /// Reveals an unidentified item's modifiers.
+    Identify,
+    /// Upgrades a normal item to rare, rolling it new explicit modifiers.
+    Alchemy,
+    /// Rerolls a rare item's explicit modifiers, keeping its implicits.
+    Chaos,
+    /// Raises a weapon's quality by 5% when normal, 2% when magic and 1% otherwise.
+    WeaponQuality,
+    /// Raises a piece of armour's quality like [`CurrencyEffect::WeaponQuality`].
+    ArmourQuality,
+}
+
+impl CurrencyEffect {
+    pub fn can_apply_to(&self, item: &Item) -> bool {
+        match self {
+            CurrencyEffect::Identify => !item.identified,
+            CurrencyEffect::Alchemy => is_equipment(item) && item.rarity == ItemRarity::Normal,
+            CurrencyEffect::Chaos => is_equipment(item) && item.identified && item.rarity == ItemRarity::Rare,
+            CurrencyEffect::WeaponQuality => {
+                matches!(item.item_class, ItemClass::Equipment(EquipmentType::Weapon(_))) && item.quality < MAX_QUALITY
+            }
+            CurrencyEffect::ArmourQuality => {
+                matches!(item.item_class, ItemClass::Equipment(EquipmentType::Armour(_))) && item.quality < MAX_QUALITY
+            }
+        }
+    }
+
+    pub fn apply(&self, item: &mut Item, rng: &mut Rng) {
+        match self {
+            CurrencyEffect::Identify => item.identify(),
+            CurrencyEffect::Alchemy => {
+                item.rarity = ItemRarity::Rare;
+                affix::roll_affixes(item, rng);
+            }
+            CurrencyEffect::Chaos => affix::roll_affixes(item, rng),
+            CurrencyEffect::WeaponQuality | CurrencyEffect::ArmourQuality => {
+                let amount = match item.rarity {
+                    ItemRarity::Normal => 5,
+                    ItemRarity::Magic => 2,
+                    ItemRarity::Rare | ItemRarity::Unique => 1,
+                };
+                item.quality = (item.quality + amount).min(MAX_QUALITY);
+            }
+        }
+    }
+}
+
+fn is_equipment(item: &Item) -> bool {
+    matches!(item.item_class, ItemClass::Equipment(_))
+}
+
+impl Display for CurrencyEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurrencyEffect::Identify => write!(f, "Identifies an item"),
+            CurrencyEffect::Alchemy => write!(f, "Upgrades a normal item to a rare item"),
+            CurrencyEffect::Chaos => write!(f, "Rerolls the modifiers on a rare item"),
+            CurrencyEffect::WeaponQuality => write!(f, "Improves the quality of a weapon"),
+            CurrencyEffect::ArmourQuality => write!(f, "Improves the quality of a piece of armour"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item_builder::item;
+
+    #[test]
+    fn alchemy_and_chaos_roll_rare_affixes_and_keep_implicits() {
+        let mut rng = Rng::seeded(7);
+        let mut ring = item("Iron Ring");
+        let implicits: Vec<String> = ring.implicits.iter().map(|x| x.description()).collect();
+
+        CurrencyEffect::Alchemy.apply(&mut ring, &mut rng);
+        assert_eq!(ring.rarity, ItemRarity::Rare);
+        assert!((3..=6).contains(&ring.modifiers.len()));
+        assert!(!CurrencyEffect::Alchemy.can_apply_to(&ring));
+
+        assert!(CurrencyEffect::Chaos.can_apply_to(&ring));
+        CurrencyEffect::Chaos.apply(&mut ring, &mut rng);
+        assert!((3..=6).contains(&ring.modifiers.len()));
+        assert_eq!(ring.implicits.iter().map(|x| x.description()).collect::<Vec<_>>(), implicits);
+    }
+
+    #[test]
+    fn quality_rises_by_rarity_up_to_the_maximum() {
+        let mut rng = Rng::seeded(7);
+        let mut sword = item("Short Sword");
+        CurrencyEffect::WeaponQuality.apply(&mut sword, &mut rng);
+        assert_eq!(sword.quality, 5);
+
+        sword.rarity = ItemRarity::Magic;
+        CurrencyEffect::WeaponQuality.apply(&mut sword, &mut rng);
+        assert_eq!(sword.quality, 7);
+
+        sword.quality = MAX_QUALITY - 1;
+        sword.rarity = ItemRarity::Normal;
+        CurrencyEffect::WeaponQuality.apply(&mut sword, &mut rng);
+        assert_eq!(sword.quality, MAX_QUALITY);
+        assert!(!CurrencyEffect::WeaponQuality.can_apply_to(&sword));
+        assert!(!CurrencyEffect::ArmourQuality.can_apply_to(&item("Short Sword")));
+    }
+
+    #[test]
+    fn identifying_reveals_the_name() {
+        let mut gloves = Item::builder()
+            .base(String::from("Leather Gloves"))
+            .name(String::from("Dusk Ward"))
+            .rarity(ItemRarity::Rare)
+            .unidentified()
+            .build()
+            .unwrap();
+        assert_eq!(gloves.display_name(), "Leather Gloves");
+        assert!(CurrencyEffect::Identify.can_apply_to(&gloves));
+
+        CurrencyEffect::Identify.apply(&mut gloves, &mut Rng::seeded(7));
+        assert_eq!(gloves.display_name(), "Dusk Ward");
+        assert!(!CurrencyEffect::Identify.can_apply_to(&gloves));
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use strum::{EnumIter, IntoEnumIterator};
+use uuid::Uuid;
+
+use crate::model::item::{ArmourType, EquipmentType, Item, ItemClass, JewelleryType};
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord, EnumIter)]
+pub enum EquipmentSlot {
+    Helmet,
+    Ring,
+    Amulet,
+    Weapon,
+    Armour,
+    OffHand,
+    Belt,
+    Gloves,
+    Boots,
+}
+
+impl EquipmentSlot {
+    pub fn for_class(item_class: &ItemClass) -> Option<EquipmentSlot> {
+        match item_class {
+            ItemClass::Equipment(equipment_type) => match equipment_type {
+                EquipmentType::Weapon(_) => Some(EquipmentSlot::Weapon),
+                EquipmentType::Armour(armour_type) => match armour_type {
+                    ArmourType::Helmet => Some(EquipmentSlot::Helmet),
+                    ArmourType::BodyArmour => Some(EquipmentSlot::Armour),
+                    ArmourType::Gloves => Some(EquipmentSlot::Gloves),
+                    ArmourType::Boots => Some(EquipmentSlot::Boots),
+                    ArmourType::Shield => Some(EquipmentSlot::OffHand),
+                },
+                EquipmentType::Jewellery(jewellery_type) => match jewellery_type {
+                    JewelleryType::Belt => Some(EquipmentSlot::Belt),
+                    JewelleryType::Ring => Some(EquipmentSlot::Ring),
+                    JewelleryType::Amulet => Some(EquipmentSlot::Amulet),
+                },
+            },
+            ItemClass::Consumable | ItemClass::Currency | ItemClass::Material | ItemClass::None => None,
+        }
+    }
+
+    /// Whether an item of the class can go in the slot, which for the off hand includes
+    /// one-handed weapons as well as shields.
+    pub fn accepts(&self, item_class: &ItemClass) -> bool {
+        match (self, item_class) {
+            (EquipmentSlot::OffHand, ItemClass::Equipment(EquipmentType::Weapon(weapon_type))) => {
+                !weapon_type.is_two_handed()
+            }
+            _ => EquipmentSlot::for_class(item_class) == Some(*self),
+        }
+    }
+}
+
+impl Display for EquipmentSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EquipmentSlot::Helmet => write!(f, "Helmet"),
+            EquipmentSlot::Amulet => write!(f, "Amulet"),
+            EquipmentSlot::Ring => write!(f, "Ring"),
+            EquipmentSlot::Weapon => write!(f, "Weapon"),
+            EquipmentSlot::Armour => write!(f, "Armour"),
+            EquipmentSlot::OffHand => write!(f, "Off Hand"),
+            EquipmentSlot::Belt => write!(f, "Belt"),
+            EquipmentSlot::Gloves => write!(f, "Gloves"),
+            EquipmentSlot::Boots => write!(f, "Boots"),
+        }
+    }
+}
+
+impl FromStr for EquipmentSlot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        EquipmentSlot::iter()
+            .find(|x| format!("{:?}", x).eq_ignore_ascii_case(&name))
+            .ok_or(format!("Unknown equipment slot: {}", s))
+    }
+}
+
+/// The items a character wears, each kept with the slot it was equipped to.
+#[derive(Debug, Default)]
+pub struct Equipment {
+    /// In slot order, so a main hand weapon always comes before an off hand one.
+    items: Vec<(EquipmentSlot, Item)>,
+}
+
+impl Equipment {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn get(&self, slot: EquipmentSlot) -> Option<&Item> {
+        self.items.iter().find(|x| x.0 == slot).map(|x| &x.1)
+    }
+
+    pub fn slot_of(&self, id: Uuid) -> Option<EquipmentSlot> {
+        self.items.iter().find(|x| x.1.id == id).map(|x| x.0)
+    }
+
+    /// Puts the item in the slot, returning whatever was there. Which slots suit the item
+    /// is left to the caller.
+    pub fn insert(&mut self, slot: EquipmentSlot, item: Item) -> Option<Item> {
+        let replaced = self.remove(slot);
+        let index = self.items.iter().position(|x| x.0 > slot).unwrap_or(self.items.len());
+        self.items.insert(index, (slot, item));
+        replaced
+    }
+
+    pub fn remove(&mut self, slot: EquipmentSlot) -> Option<Item> {
+        let index = self.items.iter().position(|x| x.0 == slot)?;
+        Some(self.items.remove(index).1)
+    }
+
+    pub fn slots(&self) -> impl Iterator<Item = (EquipmentSlot, &Item)> {
+        self.items.iter().map(|x| (x.0, &x.1))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().map(|x| &x.1)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Item> {
+        self.items.iter_mut().map(|x| &mut x.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item_builder::item;
+
+    #[test]
+    fn items_are_kept_in_slot_order() {
+        let mut equipment = Equipment::new();
+        assert!(equipment.insert(EquipmentSlot::OffHand, item("Club")).is_none());
+        assert!(equipment.insert(EquipmentSlot::Helmet, item("Sage's Circlet")).is_none());
+        let replaced = equipment.insert(EquipmentSlot::OffHand, item("Buckler"));
+        assert!(equipment.insert(EquipmentSlot::Weapon, item("Hand Axe")).is_none());
+
+        assert_eq!(replaced.map(|x| x.item_base), Some(String::from("Club")));
+        let slots: Vec<(EquipmentSlot, &str)> = equipment.slots().map(|(slot, x)| (slot, x.item_base.as_str())).collect();
+        assert_eq!(
+            slots,
+            [(EquipmentSlot::Helmet, "Sage's Circlet"), (EquipmentSlot::Weapon, "Hand Axe"), (EquipmentSlot::OffHand, "Buckler")]
+        );
+    }
+
+    #[test]
+    fn slots_are_read_back_from_their_names() {
+        assert_eq!("OffHand".parse(), Ok(EquipmentSlot::OffHand));
+        assert_eq!("off hand".parse(), Ok(EquipmentSlot::OffHand));
+        assert!("Cape".parse::<EquipmentSlot>().is_err());
+    }
+}
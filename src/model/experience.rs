@@ -0,0 +1,62 @@
+/// Attribute points granted for every level gained.
+pub const ATTRIBUTE_POINTS_PER_LEVEL: u32 = 5;
+
+/// Total experience needed to reach each level, growing polynomially:
+/// `base * (level - 1) ^ exponent`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ExperienceCurve {
+    pub base: f64,
+    pub exponent: f64,
+    pub max_level: i32,
+}
+
+impl ExperienceCurve {
+    pub fn new(base: f64, exponent: f64, max_level: i32) -> Self {
+        Self {
+            base,
+            exponent,
+            max_level,
+        }
+    }
+
+    /// Total experience at which `level` is reached. Level 1 needs none.
+    pub fn required_for(&self, level: i32) -> u64 {
+        if level <= 1 {
+            return 0;
+        }
+        (self.base * ((level - 1) as f64).powf(self.exponent)).round() as u64
+    }
+
+    /// The level a character with `experience` total experience has reached.
+    pub fn level_for(&self, experience: u64) -> i32 {
+        let mut level = 1;
+        while level < self.max_level && self.required_for(level + 1) <= experience {
+            level += 1;
+        }
+        level
+    }
+}
+
+impl Default for ExperienceCurve {
+    fn default() -> Self {
+        Self::new(100.0, 2.0, 100)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LevelUp {
+    pub level: i32,
+    pub attribute_points: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_follow_the_curve_up_to_the_maximum() {
+        let curve = ExperienceCurve::new(100.0, 2.0, 4);
+        assert_eq!([1, 2, 3, 4].map(|x| curve.required_for(x)), [0, 100, 400, 900]);
+        assert_eq!([0, 99, 100, 899, 900, u64::MAX].map(|x| curve.level_for(x)), [1, 1, 2, 3, 4, 4]);
+    }
+}
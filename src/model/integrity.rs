@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+use crate::model::affix::Affix;
+use crate::model::item::{Item, ItemClass, ItemRarity};
+use crate::model::item_base::ItemBase;
+use crate::model::player::Player;
+use crate::model::stash::Stash;
+use crate::model::stat::StatType;
+
+const MAGIC_MAX_AFFIXES: usize = 2;
+const RARE_MAX_AFFIXES: usize = 6;
+/// Requirements cannot be reduced by more than all of them.
+const MIN_REQUIREMENT_MODIFIER: i32 = -100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemLocation {
+    Inventory,
+    Equipment,
+    Stash(String),
+}
+
+impl Display for ItemLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemLocation::Inventory => write!(f, "Inventory"),
+            ItemLocation::Equipment => write!(f, "Equipment"),
+            ItemLocation::Stash(tab) => write!(f, "Stash ({})", tab),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    DuplicateId,
+    ImpossibleValue,
+    IllegalAffix,
+    AffixCount,
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ViolationKind::DuplicateId => write!(f, "duplicate id"),
+            ViolationKind::ImpossibleValue => write!(f, "impossible value"),
+            ViolationKind::IllegalAffix => write!(f, "illegal affix"),
+            ViolationKind::AffixCount => write!(f, "affix count"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub location: ItemLocation,
+    pub kind: ViolationKind,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} ({}): {}: {}",
+            self.location, self.item_name, self.item_id, self.kind, self.message
+        )
+    }
+}
+
+/// Checks a character's inventory and equipment, and the stash it shares with the other
+/// characters of its profile, so an item duplicated between them is caught too.
+pub fn check_player(player: &Player, stash: &Stash) -> Vec<Violation> {
+    let mut containers: Vec<(ItemLocation, Vec<&Item>)> = vec![
+        (ItemLocation::Inventory, player.inventory.iter().collect()),
+        (ItemLocation::Equipment, player.equippement.iter().collect()),
+    ];
+    containers.extend(stash.tabs().iter().map(|x| (ItemLocation::Stash(x.name.clone()), x.items.iter().collect())));
+    check(containers)
+}
+
+fn check(containers: Vec<(ItemLocation, Vec<&Item>)>) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut seen: HashMap<Uuid, &ItemLocation> = HashMap::new();
+
+    for (location, items) in containers.iter() {
+        for item in items.iter() {
+            let mut report = |kind, message| {
+                violations.push(Violation {
+                    item_id: item.id,
+                    item_name: item.display_name().to_string(),
+                    location: location.clone(),
+                    kind,
+                    message,
+                })
+            };
+
+            if let Some(first) = seen.insert(item.id, location) {
+                report(ViolationKind::DuplicateId, format!("id already used by an item in {}", first));
+            }
+            for (kind, message) in check_item(item) {
+                report(kind, message);
+            }
+        }
+    }
+
+    violations
+}
+
+/// Problems with a single item, regardless of where it is kept.
+pub fn check_item(item: &Item) -> Vec<(ViolationKind, String)> {
+    let mut problems = Vec::new();
+
+    if item.quantity == 0 || item.quantity > item.max_stack() {
+        problems.push((
+            ViolationKind::ImpossibleValue,
+            format!("quantity {} outside of 1 to {}", item.quantity, item.max_stack()),
+        ));
+    }
+
+    if let Some(charges) = item.charges
+        && item.max_charges().is_none_or(|max_charges| charges > max_charges)
+    {
+        problems.push((ViolationKind::ImpossibleValue, format!("{} charges on {}", charges, item.item_base)));
+    }
+
+    if item.quality > 0 && item.quality_stats().is_empty() {
+        problems.push((ViolationKind::ImpossibleValue, format!("{}% quality on {}", item.quality, item.item_base)));
+    }
+
+    if let Some(durability) = item.durability
+        && item.max_durability().is_none_or(|max_durability| durability > max_durability)
+    {
+        problems.push((
+            ViolationKind::ImpossibleValue,
+            format!("{} durability on {}", durability, item.item_base),
+        ));
+    }
+
+    for modifier in item.modifiers.iter() {
+        let description = modifier.description();
+        let values = modifier.values();
+
+        if values.contains(&0) {
+            problems.push((ViolationKind::ImpossibleValue, format!("'{}' has no effect", description)));
+        }
+        if modifier.get_affected_stat() == StatType::Requirements
+            && values.iter().any(|x| *x < MIN_REQUIREMENT_MODIFIER)
+        {
+            problems.push((
+                ViolationKind::ImpossibleValue,
+                format!("'{}' reduces requirements below nothing", description),
+            ));
+        }
+
+        if !matches!(item.item_class, ItemClass::Equipment(_)) {
+            problems.push((
+                ViolationKind::IllegalAffix,
+                format!("'{}' on an item that cannot have affixes", description),
+            ));
+            continue;
+        }
+        // Uniques have fixed modifiers of their own rather than rolled affixes.
+        if item.rarity == ItemRarity::Unique {
+            continue;
+        }
+        match Affix::find_for(modifier.as_ref()) {
+            Some(affix) if !affix.can_roll_on(item) => problems.push((
+                ViolationKind::IllegalAffix,
+                format!("'{}' cannot roll on {}", description, item.item_base),
+            )),
+            Some(affix) if values.iter().any(|x| !affix.values.contains(x)) => problems.push((
+                ViolationKind::ImpossibleValue,
+                format!(
+                    "'{}' is outside the {} to {} the affix rolls",
+                    description,
+                    affix.values.start(),
+                    affix.values.end()
+                ),
+            )),
+            Some(_) => {}
+            None => problems.push((ViolationKind::IllegalAffix, format!("'{}' is not a known affix", description))),
+        }
+    }
+
+    problems.extend(check_implicits(item));
+
+    let count = item.modifiers.len();
+    let allowed = match item.rarity {
+        ItemRarity::Normal => Some((0, 0)),
+        ItemRarity::Magic => Some((1, MAGIC_MAX_AFFIXES)),
+        ItemRarity::Rare => Some((1, RARE_MAX_AFFIXES)),
+        ItemRarity::Unique => None,
+    };
+    if let Some((min, max)) = allowed
+        && matches!(item.item_class, ItemClass::Equipment(_))
+        && !(min..=max).contains(&count)
+    {
+        problems.push((
+            ViolationKind::AffixCount,
+            format!("{:?} item with {} affixes, expected {} to {}", item.rarity, count, min, max),
+        ));
+    }
+
+    problems
+}
+
+/// Implicit modifiers come with the base, so they must be exactly the base's own.
+fn check_implicits(item: &Item) -> Vec<(ViolationKind, String)> {
+    let expected: Vec<String> = ItemBase::find(&item.item_base)
+        .map(|x| x.implicits.iter().map(|(kind, value)| kind.build(*value).description()).collect())
+        .unwrap_or_default();
+    let mut missing = expected.clone();
+
+    let mut problems = Vec::new();
+    for implicit in item.implicits.iter() {
+        let description = implicit.description();
+        match missing.iter().position(|x| *x == description) {
+            Some(index) => {
+                missing.remove(index);
+            }
+            None => problems.push((
+                ViolationKind::IllegalAffix,
+                format!("'{}' is not an implicit of {}", description, item.item_base),
+            )),
+        }
+    }
+    for description in missing {
+        problems.push((
+            ViolationKind::IllegalAffix,
+            format!("{} is missing its implicit '{}'", item.item_base, description),
+        ));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::affix::AffixKind;
+    use crate::model::character_class::CharacterClass;
+    use crate::model::modifier::{FlatStatModifier, ModifierTargetKind};
+    use crate::model::stash::StashTab;
+
+    fn magic_ring(stat: StatType, value: i32) -> Item {
+        Item::builder()
+            .base(String::from("Iron Ring"))
+            .rarity(ItemRarity::Magic)
+            .with_modifier(FlatStatModifier { value, stat, target: ModifierTargetKind::Character })
+            .build()
+            .unwrap()
+    }
+
+    fn kinds(item: &Item) -> Vec<ViolationKind> {
+        check_item(item).into_iter().map(|x| x.0).collect()
+    }
+
+    #[test]
+    fn rolled_affixes_are_legal() {
+        assert!(check_item(&magic_ring(StatType::Strength, 20)).is_empty());
+    }
+
+    #[test]
+    fn affixes_are_checked_against_the_affix_table() {
+        assert_eq!(kinds(&magic_ring(StatType::Strength, 99)), [ViolationKind::ImpossibleValue]);
+        assert_eq!(kinds(&magic_ring(StatType::Range, 5)), [ViolationKind::IllegalAffix]);
+
+        let mut ring = magic_ring(StatType::Strength, 20);
+        ring.modifiers = vec![AffixKind::IncreasedDamage(ModifierTargetKind::Item).build(50)];
+        assert_eq!(kinds(&ring), [ViolationKind::IllegalAffix]);
+
+        // Only flat strength rolls, so a percentage of it is no affix at all.
+        ring.modifiers = vec![AffixKind::Percent(StatType::Strength, ModifierTargetKind::Character).build(20)];
+        assert_eq!(kinds(&ring), [ViolationKind::IllegalAffix]);
+    }
+
+    #[test]
+    fn implicits_must_be_the_bases_own() {
+        let mut ring = magic_ring(StatType::Strength, 20);
+        ring.implicits = vec![AffixKind::Flat(StatType::Life, ModifierTargetKind::Character).build(40)];
+        assert_eq!(kinds(&ring), [ViolationKind::IllegalAffix, ViolationKind::IllegalAffix]);
+
+        ring.implicits.clear();
+        assert_eq!(kinds(&ring), [ViolationKind::IllegalAffix]);
+    }
+
+    #[test]
+    fn duplicates_between_the_character_and_the_stash_are_found() {
+        let mut player = Player::new(CharacterClass::find("Warrior").unwrap());
+        let ring = magic_ring(StatType::Strength, 20);
+        let mut copy = magic_ring(StatType::Strength, 20);
+        copy.id = ring.id;
+        player.pickup(ring).unwrap();
+        let mut gear = StashTab::new(String::from("Gear"));
+        gear.items.add(copy).unwrap();
+        let stash = Stash::from_tabs(vec![gear]);
+
+        let violations = check_player(&player, &stash);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::DuplicateId);
+        assert_eq!(violations[0].location, ItemLocation::Stash(String::from("Gear")));
+    }
+}
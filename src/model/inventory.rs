@@ -1,25 +1,340 @@
-use crate::model::item::Item;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+use crate::model::item::{Item, ItemClass};
+use crate::model::item_sort::SortKey;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GridPosition {
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InventoryErrorReason {
+    NoRoom,
+    TooManyItems,
+    TooHeavy,
+}
+
+#[derive(Debug)]
+pub struct InventoryError {
+    pub item: Box<Item>,
+    pub reason: InventoryErrorReason,
+}
+
+impl Display for InventoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let name = self.item.display_name();
+        match self.reason {
+            InventoryErrorReason::NoRoom => write!(f, "Inventory error: no room for {}", name),
+            InventoryErrorReason::TooManyItems => write!(f, "Inventory error: too many items to add {}", name),
+            InventoryErrorReason::TooHeavy => write!(f, "Inventory error: {} is too heavy to carry", name),
+        }
+    }
+}
+
+impl InventoryError {
+    pub(super) fn new(item: Item, reason: InventoryErrorReason) -> Self {
+        Self { item: Box::new(item), reason }
+    }
+}
+
+pub struct InventoryGrid {
+    pub width: usize,
+    pub height: usize,
+    positions: HashMap<Uuid, GridPosition>,
+}
 
 pub struct Inventory {
-    pub(super) items: Vec<Item>
+    pub(super) items: Vec<Item>,
+    grid: Option<InventoryGrid>,
+    /// Most separate items, counting a stack as one, the inventory holds.
+    pub max_slots: Option<usize>,
 }
 
 impl Inventory {
     pub fn new() -> Self {
         Self {
             items: vec![],
+            grid: None,
+            max_slots: None,
         }
     }
 
-    pub fn add(&mut self, item: Item) {
+    pub fn with_grid(width: usize, height: usize) -> Self {
+        Self {
+            items: vec![],
+            grid: Some(InventoryGrid {
+                width,
+                height,
+                positions: HashMap::new(),
+            }),
+            max_slots: None,
+        }
+    }
+
+    pub fn total_weight(&self) -> f32 {
+        self.items.iter().map(|x| x.weight()).sum()
+    }
+
+    fn check_limits(&self, item: Item) -> Result<Item, InventoryError> {
+        if self.max_slots.is_some_and(|max_slots| self.items.len() >= max_slots) {
+            return Err(InventoryError::new(item, InventoryErrorReason::TooManyItems));
+        }
+        Ok(item)
+    }
+
+    /// Adds the item at the first free position of the grid, handing it back if there is no room.
+    pub fn add(&mut self, item: Item) -> Result<(), InventoryError> {
+        let item = self.check_limits(item)?;
+        if let Some(grid) = &self.grid {
+            let Some(position) = grid.find_free_position(&self.items, item.size()) else {
+                return Err(InventoryError::new(item, InventoryErrorReason::NoRoom));
+            };
+            return self.place(item, position);
+        }
+
+        self.items.push(item);
+        Ok(())
+    }
+
+    pub fn place(&mut self, item: Item, position: GridPosition) -> Result<(), InventoryError> {
+        let item = self.check_limits(item)?;
+        if let Some(grid) = &mut self.grid {
+            if !grid.is_free(&self.items, position, item.size()) {
+                return Err(InventoryError::new(item, InventoryErrorReason::NoRoom));
+            }
+            grid.positions.insert(item.id, position);
+        }
+
         self.items.push(item);
+        Ok(())
+    }
+
+    fn remove_at(&mut self, index: usize) -> Item {
+        let item = self.items.remove(index);
+        if let Some(grid) = &mut self.grid {
+            grid.positions.remove(&item.id);
+        }
+        item
+    }
+
+    /// Adds the item, topping up existing stacks of the same base first and placing
+    /// whatever is left over as a new stack. Either all of the item is added or, when the
+    /// leftover does not fit, nothing changes and the whole item is handed back.
+    pub fn add_stacking(&mut self, mut item: Item) -> Result<(), InventoryError> {
+        let room: u32 = self
+            .items
+            .iter()
+            .filter(|x| x.can_stack_with(&item))
+            .map(|x| x.max_stack().saturating_sub(x.quantity))
+            .sum();
+        if room == 0 {
+            return self.add(item);
+        }
+
+        if item.quantity > room {
+            let rest = item.split_off(item.quantity - room).expect("stackable items can be split");
+            if let Err(mut e) = self.add(rest) {
+                item.quantity += e.item.quantity;
+                e.item = Box::new(item);
+                return Err(e);
+            }
+        }
+        self.add_to_stacks(item);
+        Ok(())
+    }
+
+    /// Tops up existing stacks of the same base with the item's quantity and returns
+    /// whatever did not fit, if anything.
+    fn add_to_stacks(&mut self, mut item: Item) -> Option<Item> {
+        for stack in self.items.iter_mut() {
+            if !stack.can_stack_with(&item) {
+                continue;
+            }
+            let moved = item.quantity.min(stack.max_stack().saturating_sub(stack.quantity));
+            stack.quantity += moved;
+            item.quantity -= moved;
+            if item.quantity == 0 {
+                return None;
+            }
+        }
+        Some(item)
+    }
+
+    /// Takes `quantity` out of the stack with the given id, removing the stack entirely
+    /// when all of it is taken.
+    pub fn take(&mut self, id: Uuid, quantity: u32) -> Option<Item> {
+        let index = self.items.iter().position(|x| x.id == id)?;
+        if quantity >= self.items[index].quantity {
+            return Some(self.remove_at(index));
+        }
+        self.items[index].split_off(quantity)
+    }
+
+    /// Splits `quantity` off a stack into a new stack placed at the first free position.
+    pub fn split(&mut self, id: Uuid, quantity: u32) -> bool {
+        let Some(index) = self.items.iter().position(|x| x.id == id) else {
+            return false;
+        };
+        let Some(split) = self.items[index].split_off(quantity) else {
+            return false;
+        };
+
+        match self.add(split) {
+            Ok(()) => true,
+            Err(e) => {
+                self.items[index].quantity += e.item.quantity;
+                false
+            }
+        }
+    }
+
+    /// Moves as much of the `source` stack as fits into the `target` stack,
+    /// removing `source` once it is empty.
+    pub fn merge(&mut self, source: Uuid, target: Uuid) -> bool {
+        let (Some(source_index), Some(target_index)) = (
+            self.items.iter().position(|x| x.id == source),
+            self.items.iter().position(|x| x.id == target),
+        ) else {
+            return false;
+        };
+
+        if source_index == target_index || !self.items[source_index].can_stack_with(&self.items[target_index]) {
+            return false;
+        }
+
+        let target_item = &self.items[target_index];
+        let moved = self.items[source_index]
+            .quantity
+            .min(target_item.max_stack().saturating_sub(target_item.quantity));
+        self.items[target_index].quantity += moved;
+        self.items[source_index].quantity -= moved;
+
+        if self.items[source_index].quantity == 0 {
+            self.remove_at(source_index);
+        }
+        moved > 0
+    }
+
+    pub fn position_of(&self, item: &Item) -> Option<GridPosition> {
+        self.grid.as_ref()?.positions.get(&item.id).copied()
+    }
+
+    pub fn grid(&self) -> Option<&InventoryGrid> {
+        self.grid.as_ref()
+    }
+
+    /// Sorts the items and packs the grid in the new order, keeping the current
+    /// layout if the sorted items do not all fit.
+    pub fn sort_by(&mut self, key: SortKey) -> bool {
+        self.items.sort_by(|a, b| key.compare(a, b));
+        let order: Vec<&Item> = self.items.iter().collect();
+        Self::arrange(&mut self.grid, order)
     }
-    
+
+    pub fn filter<'a, P>(&'a self, predicate: P) -> impl Iterator<Item = &'a Item>
+    where
+        P: Fn(&Item) -> bool + 'a,
+    {
+        self.items.iter().filter(move |x| predicate(x))
+    }
+
+    /// Groups the items by class, classes in ascending order and items in inventory order.
+    pub fn group_by_class(&self) -> Vec<(ItemClass, Vec<&Item>)> {
+        let mut groups: Vec<(ItemClass, Vec<&Item>)> = Vec::new();
+        for item in self.items.iter() {
+            match groups.iter_mut().find(|(class, _)| *class == item.item_class) {
+                Some((_, items)) => items.push(item),
+                None => groups.push((item.item_class.clone(), vec![item])),
+            }
+        }
+        groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+        groups
+    }
+
+    /// Repacks every item, largest first, from the top left corner column by column.
+    /// The current layout is kept if the items cannot all be packed.
+    pub fn auto_arrange(&mut self) -> bool {
+        let mut order: Vec<&Item> = self.items.iter().collect();
+        order.sort_by_key(|x| {
+            let (width, height) = x.size();
+            std::cmp::Reverse((width * height, height))
+        });
+        Self::arrange(&mut self.grid, order)
+    }
+
+    fn arrange(grid: &mut Option<InventoryGrid>, order: Vec<&Item>) -> bool {
+        let Some(grid) = grid else {
+            return false;
+        };
+
+        let mut arranged = InventoryGrid {
+            width: grid.width,
+            height: grid.height,
+            positions: HashMap::new(),
+        };
+        let mut placed: Vec<&Item> = Vec::new();
+
+        for item in order {
+            let Some(position) = arranged.find_free_position(placed.iter().copied(), item.size()) else {
+                return false;
+            };
+            arranged.positions.insert(item.id, position);
+            placed.push(item);
+        }
+
+        grid.positions = arranged.positions;
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
     pub fn iter(&'_ self) -> core::slice::Iter<'_, Item>{
         self.items.iter()
     }
 }
 
+impl InventoryGrid {
+    fn is_free<'a, I>(&self, items: I, position: GridPosition, size: (usize, usize)) -> bool
+    where
+        I: IntoIterator<Item = &'a Item>,
+    {
+        let (width, height) = size;
+        if position.x + width > self.width || position.y + height > self.height {
+            return false;
+        }
+
+        items.into_iter().all(|item| {
+            let Some(origin) = self.positions.get(&item.id) else {
+                return true;
+            };
+            let (item_width, item_height) = item.size();
+            position.x >= origin.x + item_width
+                || origin.x >= position.x + width
+                || position.y >= origin.y + item_height
+                || origin.y >= position.y + height
+        })
+    }
+
+    fn find_free_position<'a, I>(&self, items: I, size: (usize, usize)) -> Option<GridPosition>
+    where
+        I: IntoIterator<Item = &'a Item> + Clone,
+    {
+        (0..self.width)
+            .flat_map(|x| (0..self.height).map(move |y| GridPosition { x, y }))
+            .find(|position| self.is_free(items.clone(), *position, size))
+    }
+}
+
 impl IntoIterator for Inventory {
     type Item = Item;
 
@@ -28,4 +343,152 @@ impl IntoIterator for Inventory {
     fn into_iter(self) -> Self::IntoIter {
         self.items.into_iter()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item_builder::item;
+    use crate::model::modifier::{FlatStatModifier, ModifierTargetKind};
+    use crate::model::stat::StatType;
+
+    #[test]
+    fn items_fill_the_grid_column_by_column() {
+        let mut inventory = Inventory::with_grid(4, 4);
+        let axe = item("Hand Axe");
+        let ring = item("Iron Ring");
+        let amulet = item("Jade Amulet");
+        let ids = [axe.id, ring.id, amulet.id];
+        for item in [axe, ring, amulet] {
+            inventory.add(item).unwrap();
+        }
+
+        let positions: Vec<Option<GridPosition>> = ids
+            .iter()
+            .map(|id| inventory.position_of(inventory.iter().find(|x| x.id == *id).unwrap()))
+            .collect();
+        assert_eq!(
+            positions,
+            [Some(GridPosition { x: 0, y: 0 }), Some(GridPosition { x: 0, y: 3 }), Some(GridPosition { x: 1, y: 0 })]
+        );
+    }
+
+    #[test]
+    fn items_cannot_overlap_or_leave_the_grid() {
+        let mut inventory = Inventory::with_grid(4, 4);
+        inventory.place(item("Hand Axe"), GridPosition { x: 0, y: 0 }).unwrap();
+
+        assert!(inventory.place(item("Iron Ring"), GridPosition { x: 0, y: 2 }).is_err());
+        assert!(inventory.place(item("Claymore"), GridPosition { x: 3, y: 0 }).is_err());
+        inventory.place(item("Claymore"), GridPosition { x: 2, y: 0 }).unwrap();
+        assert_eq!(inventory.len(), 2);
+    }
+
+    #[test]
+    fn auto_arrange_makes_room_for_larger_items() {
+        let mut inventory = Inventory::with_grid(3, 4);
+        for x in 0..3 {
+            inventory.place(item("Iron Ring"), GridPosition { x, y: 1 }).unwrap();
+        }
+        assert!(inventory.add(item("Hand Axe")).is_err());
+
+        assert!(inventory.auto_arrange());
+        inventory.add(item("Hand Axe")).unwrap();
+        assert!(!Inventory::new().auto_arrange());
+    }
+
+    fn potions(quantity: u32) -> Item {
+        Item::builder()
+            .base(String::from("Health Potion"))
+            .quantity(quantity)
+            .build()
+            .unwrap()
+    }
+
+    fn quantities(inventory: &Inventory) -> Vec<u32> {
+        inventory.iter().map(|x| x.quantity).collect()
+    }
+
+    #[test]
+    fn stacks_are_topped_up_before_a_new_stack_is_placed() {
+        let mut inventory = Inventory::with_grid(2, 1);
+        inventory.add(potions(8)).unwrap();
+
+        inventory.add_stacking(potions(5)).unwrap();
+        assert_eq!(quantities(&inventory), vec![10, 3]);
+    }
+
+    #[test]
+    fn a_leftover_that_does_not_fit_leaves_the_stacks_untouched() {
+        let mut inventory = Inventory::with_grid(1, 1);
+        inventory.add(potions(8)).unwrap();
+
+        let error = inventory.add_stacking(potions(5)).unwrap_err();
+        assert_eq!(error.reason, InventoryErrorReason::NoRoom);
+        assert_eq!(error.item.quantity, 5);
+        assert_eq!(quantities(&inventory), vec![8]);
+    }
+
+    #[test]
+    fn oversized_stacks_take_nothing_more() {
+        let mut inventory = Inventory::with_grid(2, 1);
+        inventory.add(potions(10)).unwrap();
+        inventory.items[0].quantity = 12;
+
+        inventory.add_stacking(potions(3)).unwrap();
+        assert_eq!(quantities(&inventory), vec![12, 3]);
+    }
+
+    #[test]
+    fn split_moves_part_of_a_stack_to_a_new_stack() {
+        let mut inventory = Inventory::with_grid(2, 1);
+        inventory.add(potions(8)).unwrap();
+        let id = inventory.items[0].id;
+
+        assert!(!inventory.split(id, 0));
+        assert!(!inventory.split(id, 8));
+        assert!(inventory.split(id, 3));
+        assert_eq!(quantities(&inventory), vec![5, 3]);
+    }
+
+    #[test]
+    fn split_without_room_keeps_the_stack_whole() {
+        let mut inventory = Inventory::with_grid(1, 1);
+        inventory.add(potions(8)).unwrap();
+        let id = inventory.items[0].id;
+
+        assert!(!inventory.split(id, 3));
+        assert_eq!(quantities(&inventory), vec![8]);
+    }
+
+    #[test]
+    fn merge_moves_what_fits_and_removes_emptied_stacks() {
+        let mut inventory = Inventory::with_grid(3, 1);
+        inventory.add(potions(8)).unwrap();
+        inventory.add(potions(5)).unwrap();
+        inventory.add(potions(2)).unwrap();
+        let ids: Vec<Uuid> = inventory.iter().map(|x| x.id).collect();
+
+        assert!(!inventory.merge(ids[0], ids[0]));
+        assert!(inventory.merge(ids[1], ids[0]));
+        assert_eq!(quantities(&inventory), vec![10, 3, 2]);
+        assert!(!inventory.merge(ids[2], ids[0]));
+        assert!(inventory.merge(ids[2], ids[1]));
+        assert_eq!(quantities(&inventory), vec![10, 5]);
+    }
+
+    #[test]
+    fn items_with_modifiers_do_not_stack() {
+        let mut inventory = Inventory::with_grid(2, 1);
+        inventory.add(item("Health Potion")).unwrap();
+        let potion = Item::builder()
+            .base(String::from("Health Potion"))
+            .with_modifier(FlatStatModifier { value: 5, stat: StatType::Life, target: ModifierTargetKind::Character })
+            .build()
+            .unwrap();
+
+        assert!(!inventory.items[0].can_stack_with(&potion));
+        inventory.add_stacking(potion).unwrap();
+        assert_eq!(inventory.len(), 2);
+    }
+}
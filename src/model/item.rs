@@ -1,10 +1,17 @@
 use uuid::Uuid;
 
+use crate::model::consumable::UseEffect;
+use crate::model::currency::CurrencyEffect;
+use crate::model::item_base::ItemBase;
 use crate::model::item_builder::ItemBuilder;
 use crate::model::modifier::{Modifier, ModifierPass, ModifierTarget};
 use crate::model::requirement::{RequirementBlock};
-use crate::model::stat::{StatBlock};
+use crate::model::stat::{StatBlock, StatType};
 use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+/// The highest quality currency can raise an item to.
+pub const MAX_QUALITY: u32 = 20;
 
 #[derive(Debug)]
 pub struct Item {
@@ -13,8 +20,23 @@ pub struct Item {
     pub name: Option<String>,
     pub rarity: ItemRarity,
     pub item_class: ItemClass,
+    pub item_level: u32,
+    pub quantity: u32,
+    /// Uses left on a consumable that is not used up in one go, such as a flask.
+    pub charges: Option<u32>,
+    /// Wear left on weapons and armour. At zero the item is broken and does nothing
+    /// until repaired.
+    pub durability: Option<u32>,
+    /// Unidentified items keep their modifiers hidden, and without effect, until identified.
+    pub identified: bool,
+    /// Percentage by which the base damage of a weapon or the base defence of armour is
+    /// raised, normally up to [`MAX_QUALITY`].
+    pub quality: u32,
     pub requirements: RequirementBlock,
     pub base_stats: StatBlock,
+    /// Modifiers that come with the item's base, which crafting leaves alone.
+    pub implicits: Vec<Box<dyn Modifier>>,
+    /// Explicit modifiers: the affixes rolled or crafted onto the item.
     pub modifiers: Vec<Box<dyn Modifier>>,
 }
 
@@ -22,6 +44,139 @@ impl Item {
     pub fn builder() -> ItemBuilder {
         ItemBuilder::new()
     }
+
+    pub fn display_name(&self) -> &str {
+        match &self.name {
+            Some(name) if self.identified => name,
+            _ => &self.item_base,
+        }
+    }
+
+    /// The explicit modifiers that are visible and take effect, which is none of them until
+    /// the item is identified.
+    pub fn known_modifiers(&self) -> &[Box<dyn Modifier>] {
+        match self.identified {
+            true => &self.modifiers,
+            false => &[],
+        }
+    }
+
+    /// Every modifier that takes effect: the implicits, then the known explicits.
+    pub fn active_modifiers(&self) -> impl Iterator<Item = &Box<dyn Modifier>> {
+        self.implicits.iter().chain(self.known_modifiers())
+    }
+
+    pub fn identify(&mut self) {
+        self.identified = true;
+    }
+
+    pub fn currency_effect(&self) -> Option<CurrencyEffect> {
+        ItemBase::find(&self.item_base).and_then(|x| x.currency_effect)
+    }
+
+    /// Width and height of the item in inventory cells, taken from its base.
+    pub fn size(&self) -> (usize, usize) {
+        ItemBase::find(&self.item_base)
+            .map(|x| (x.width, x.height))
+            .unwrap_or((1, 1))
+    }
+
+    /// Weight of the whole stack, taken from its base.
+    pub fn weight(&self) -> f32 {
+        ItemBase::find(&self.item_base)
+            .map(|x| x.weight * self.quantity as f32)
+            .unwrap_or(0.0)
+    }
+
+    pub fn use_effect(&self) -> Option<UseEffect> {
+        ItemBase::find(&self.item_base).and_then(|x| x.use_effect)
+    }
+
+    pub fn max_charges(&self) -> Option<u32> {
+        ItemBase::find(&self.item_base).and_then(|x| x.max_charges)
+    }
+
+    pub fn max_durability(&self) -> Option<u32> {
+        ItemBase::find(&self.item_base).and_then(|x| x.max_durability)
+    }
+
+    pub fn weapon_type(&self) -> Option<&WeaponType> {
+        match &self.item_class {
+            ItemClass::Equipment(EquipmentType::Weapon(weapon_type)) => Some(weapon_type),
+            _ => None,
+        }
+    }
+
+    pub fn is_two_handed(&self) -> bool {
+        self.weapon_type().is_some_and(|x| x.is_two_handed())
+    }
+
+    /// One-handed weapons can be dual wielded, the second one going in the off hand.
+    pub fn is_one_handed_weapon(&self) -> bool {
+        self.weapon_type().is_some_and(|x| !x.is_two_handed())
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.durability == Some(0)
+    }
+
+    pub fn wear(&mut self, amount: u32) {
+        if let Some(durability) = &mut self.durability {
+            *durability = durability.saturating_sub(amount);
+        }
+    }
+
+    pub fn repair(&mut self) {
+        if self.durability.is_some() {
+            self.durability = self.max_durability().or(self.durability);
+        }
+    }
+
+    pub fn max_stack(&self) -> u32 {
+        ItemBase::find(&self.item_base)
+            .map(|x| x.max_stack)
+            .unwrap_or(1)
+    }
+
+    pub fn can_stack_with(&self, other: &Item) -> bool {
+        self.max_stack() > 1
+            && self.item_base == other.item_base
+            && self.name == other.name
+            && self.charges == other.charges
+            && self.durability == other.durability
+            && self.identified == other.identified
+            && self.quality == other.quality
+            && self.modifiers.is_empty()
+            && other.modifiers.is_empty()
+            && self.implicits.is_empty()
+            && other.implicits.is_empty()
+    }
+
+    /// Splits `quantity` off this stack into a new item, leaving the rest in place.
+    pub fn split_off(&mut self, quantity: u32) -> Option<Item> {
+        if quantity == 0 || quantity >= self.quantity || !self.modifiers.is_empty() || !self.implicits.is_empty() {
+            return None;
+        }
+
+        self.quantity -= quantity;
+        Some(Item {
+            id: Uuid::new_v4(),
+            item_base: self.item_base.clone(),
+            name: self.name.clone(),
+            rarity: self.rarity,
+            item_class: self.item_class.clone(),
+            item_level: self.item_level,
+            quantity,
+            charges: self.charges,
+            durability: self.durability,
+            identified: self.identified,
+            quality: self.quality,
+            requirements: self.requirements.clone(),
+            base_stats: self.base_stats.clone(),
+            implicits: Vec::new(),
+            modifiers: Vec::new(),
+        })
+    }
 }
 
 impl PartialEq for Item {
@@ -32,11 +187,27 @@ impl PartialEq for Item {
 
 impl Item {
 
+    /// The base stats that quality raises: damage on weapons and defence on armour.
+    pub fn quality_stats(&self) -> &'static [StatType] {
+        match self.item_class {
+            ItemClass::Equipment(EquipmentType::Weapon(_)) => &[StatType::MinimumDamage, StatType::MaximumDamage],
+            ItemClass::Equipment(EquipmentType::Armour(_)) => &[StatType::Armour, StatType::Evasion, StatType::EnergyShield],
+            _ => &[],
+        }
+    }
+
     pub fn get_derived_stats(&self) -> StatBlock {
         let mut stats = self.base_stats.clone();
 
+        // Quality raises the base values before any modifier applies.
+        for stat_type in self.quality_stats() {
+            if let Some(stat) = stats.get_mut(*stat_type) {
+                stat.value = (stat.value * (100 + self.quality as i32) + 50) / 100;
+            }
+        }
+
         for pass in [ModifierPass::Flat, ModifierPass::Increased] {
-            for modifier in self.modifiers.iter().filter(| x | *x.pass() == pass) {
+            for modifier in self.active_modifiers().filter(| x | *x.pass() == pass) {
                 match pass {
                     ModifierPass::Requirements => {}
                     _ => modifier.apply_to(ModifierTarget::Item(&mut stats)),
@@ -50,7 +221,7 @@ impl Item {
     pub fn get_derived_requirements(&self) -> RequirementBlock {
         let mut reqs = self.requirements.clone();
 
-        for modifier in self.modifiers.iter().filter(| x | *x.pass() == ModifierPass::Requirements) {
+        for modifier in self.active_modifiers().filter(| x | *x.pass() == ModifierPass::Requirements) {
                 modifier.apply_to(ModifierTarget::Requirements(&mut reqs))
 
         }
@@ -59,7 +230,7 @@ impl Item {
     }    
 }
 
-#[derive(Debug, Copy,Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ItemRarity {
     Normal,
     Magic,
@@ -67,9 +238,12 @@ pub enum ItemRarity {
     Unique,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ItemClass {
     Equipment(EquipmentType),
+    Consumable,
+    Currency,
+    Material,
     None
 }
 
@@ -77,12 +251,64 @@ impl Display for ItemClass {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ItemClass::Equipment(equipment_type) => write!(f, "{}", equipment_type.to_string()),
+            ItemClass::Consumable => write!(f, "Consumable"),
+            ItemClass::Currency => write!(f, "Currency"),
+            ItemClass::Material => write!(f, "Crafting Material"),
             ItemClass::None => write!(f, "{}", "None")
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+impl FromStr for ItemClass {
+    type Err = String;
+
+    /// Reads a class back from its display name, ignoring case and spaces.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+        let class = match name.as_str() {
+            "helmet" => ItemClass::Equipment(EquipmentType::Armour(ArmourType::Helmet)),
+            "bodyarmour" => ItemClass::Equipment(EquipmentType::Armour(ArmourType::BodyArmour)),
+            "gloves" => ItemClass::Equipment(EquipmentType::Armour(ArmourType::Gloves)),
+            "boots" => ItemClass::Equipment(EquipmentType::Armour(ArmourType::Boots)),
+            "shield" => ItemClass::Equipment(EquipmentType::Armour(ArmourType::Shield)),
+            "sword" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Sword)),
+            "twohandsword" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::TwoHandSword)),
+            "dagger" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Dagger)),
+            "axe" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Axe)),
+            "twohandaxe" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::TwoHandAxe)),
+            "mace" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Mace)),
+            "twohandmace" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::TwoHandMace)),
+            "bow" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Bow)),
+            "staff" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Staff)),
+            "wand" => ItemClass::Equipment(EquipmentType::Weapon(WeaponType::Wand)),
+            "belt" => ItemClass::Equipment(EquipmentType::Jewellery(JewelleryType::Belt)),
+            "ring" => ItemClass::Equipment(EquipmentType::Jewellery(JewelleryType::Ring)),
+            "amulet" => ItemClass::Equipment(EquipmentType::Jewellery(JewelleryType::Amulet)),
+            "consumable" => ItemClass::Consumable,
+            "currency" => ItemClass::Currency,
+            "craftingmaterial" => ItemClass::Material,
+            "none" => ItemClass::None,
+            _ => return Err(format!("Unknown item class: {}", s)),
+        };
+        Ok(class)
+    }
+}
+
+impl FromStr for ItemRarity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "normal" => Ok(ItemRarity::Normal),
+            "magic" => Ok(ItemRarity::Magic),
+            "rare" => Ok(ItemRarity::Rare),
+            "unique" => Ok(ItemRarity::Unique),
+            _ => Err(format!("Unknown rarity: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum EquipmentType {
     Armour(ArmourType),
     Weapon(WeaponType),
@@ -99,7 +325,7 @@ impl Display for EquipmentType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ArmourType {
     Helmet,
     BodyArmour,
@@ -121,24 +347,48 @@ impl Display for ArmourType {
     }
 }
 
-#[derive(Debug,Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum WeaponType {
     Sword,
+    TwoHandSword,
     Dagger,
     Axe,
+    TwoHandAxe,
+    Mace,
+    TwoHandMace,
+    Bow,
+    Staff,
+    Wand,
+}
+
+impl WeaponType {
+    /// Two-handed weapons leave no hand free for a shield or a second weapon.
+    pub fn is_two_handed(&self) -> bool {
+        matches!(
+            self,
+            WeaponType::TwoHandSword | WeaponType::TwoHandAxe | WeaponType::TwoHandMace | WeaponType::Bow | WeaponType::Staff
+        )
+    }
 }
 
 impl Display for WeaponType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             WeaponType::Sword => write!(f, "Sword"),
+            WeaponType::TwoHandSword => write!(f, "Two Hand Sword"),
             WeaponType::Dagger => write!(f, "Dagger"),
             WeaponType::Axe => write!(f, "Axe"),
+            WeaponType::TwoHandAxe => write!(f, "Two Hand Axe"),
+            WeaponType::Mace => write!(f, "Mace"),
+            WeaponType::TwoHandMace => write!(f, "Two Hand Mace"),
+            WeaponType::Bow => write!(f, "Bow"),
+            WeaponType::Staff => write!(f, "Staff"),
+            WeaponType::Wand => write!(f, "Wand"),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum JewelleryType {
     Belt,
     Ring,
@@ -154,3 +404,67 @@ impl Display for JewelleryType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item_builder::item;
+    use crate::model::modifier::{FlatStatModifier, ModifierTargetKind};
+
+    #[test]
+    fn items_get_their_bases_implicits() {
+        let ring = item("Iron Ring");
+        assert_eq!(ring.implicits.len(), 1);
+        assert_eq!(ring.implicits[0].get_affected_stat(), StatType::Life);
+        assert!(ring.known_modifiers().is_empty());
+    }
+
+    #[test]
+    fn implicits_work_before_identification() {
+        let mut ring = Item::builder()
+            .base(String::from("Iron Ring"))
+            .with_modifier(FlatStatModifier { value: 5, stat: StatType::Strength, target: ModifierTargetKind::Character })
+            .build()
+            .unwrap();
+        ring.identified = false;
+
+        let affected: Vec<StatType> = ring.active_modifiers().map(|x| x.get_affected_stat()).collect();
+        assert_eq!(affected, [StatType::Life]);
+        ring.identify();
+        assert_eq!(ring.active_modifiers().count(), 2);
+    }
+
+    #[test]
+    fn only_identical_items_stack() {
+        let potion = item("Health Potion");
+        let mut named = item("Health Potion");
+        named.name = Some(String::from("Fizzy Draught"));
+        let mut polished = item("Health Potion");
+        polished.quality = 10;
+        let mut unidentified = item("Health Potion");
+        unidentified.identified = false;
+
+        assert!(potion.can_stack_with(&item("Health Potion")));
+        for other in [named, polished, unidentified] {
+            assert!(!potion.can_stack_with(&other));
+        }
+    }
+
+    #[test]
+    fn quality_raises_base_values_before_modifiers() {
+        let mut vest = Item::builder()
+            .base(String::from("Plate Vest"))
+            .with_modifier(FlatStatModifier { value: 10, stat: StatType::Armour, target: ModifierTargetKind::Item })
+            .build()
+            .unwrap();
+        vest.quality = 20;
+        assert_eq!(vest.get_derived_stats().get(StatType::Armour).map(|x| x.value), Some(46));
+
+        let mut axe = item("Hand Axe");
+        axe.quality = 20;
+        let stats = axe.get_derived_stats();
+        let value = |stats: &StatBlock, stat_type| stats.get(stat_type).map(|x| x.value);
+        assert_eq!([StatType::MinimumDamage, StatType::MaximumDamage].map(|x| value(&stats, x)), [Some(2), Some(7)]);
+        assert_eq!(value(&stats, StatType::AttackSpeed), value(&axe.base_stats, StatType::AttackSpeed));
+    }
+}
@@ -0,0 +1,227 @@
+use std::sync::LazyLock;
+
+use std::time::Duration;
+
+use crate::model::affix::AffixKind;
+use crate::model::buff::{Buff, StackingRule};
+use crate::model::consumable::UseEffect;
+use crate::model::currency::CurrencyEffect;
+use crate::model::item::{ArmourType, EquipmentType, ItemClass, JewelleryType, WeaponType};
+use crate::model::modifier::ModifierTargetKind;
+use crate::model::stat::StatType;
+
+const WEAPON_DURABILITY: u32 = 60;
+const ARMOUR_DURABILITY: u32 = 50;
+
+#[derive(Debug, Clone)]
+pub struct ItemBase {
+    pub name: String,
+    pub item_class: ItemClass,
+    pub width: usize,
+    pub height: usize,
+    pub max_stack: u32,
+    pub weight: f32,
+    /// Gold a vendor pays for a normal item of this base, before its modifiers.
+    pub value: u32,
+    pub use_effect: Option<UseEffect>,
+    pub max_charges: Option<u32>,
+    /// What applying an item of this base to another item does, for currency.
+    pub currency_effect: Option<CurrencyEffect>,
+    pub max_durability: Option<u32>,
+    /// The only character class that can equip items of this base, if restricted.
+    pub class_restriction: Option<&'static str>,
+    /// Stats items of this base start with, such as a weapon's damage.
+    pub stats: Vec<(StatType, i32)>,
+    /// Modifiers every item of this base has, such as a ring's inherent life.
+    pub implicits: Vec<(AffixKind, i32)>,
+}
+
+impl ItemBase {
+    fn new(name: &str, item_class: ItemClass, width: usize, height: usize, weight: f32, value: u32) -> Self {
+        let max_durability = match &item_class {
+            ItemClass::Equipment(EquipmentType::Weapon(_)) => Some(WEAPON_DURABILITY),
+            ItemClass::Equipment(EquipmentType::Armour(_)) => Some(ARMOUR_DURABILITY),
+            _ => None,
+        };
+        Self {
+            name: String::from(name),
+            item_class,
+            width,
+            height,
+            max_stack: 1,
+            weight,
+            value,
+            use_effect: None,
+            max_charges: None,
+            currency_effect: None,
+            max_durability,
+            class_restriction: None,
+            stats: Vec::new(),
+            implicits: Vec::new(),
+        }
+    }
+
+    fn with_effect(mut self, use_effect: UseEffect) -> Self {
+        self.use_effect = Some(use_effect);
+        self
+    }
+
+    /// Lets the item be used several times before it runs dry, rather than being used up.
+    fn with_charges(mut self, max_charges: u32) -> Self {
+        self.max_charges = Some(max_charges);
+        self
+    }
+
+    fn with_currency_effect(mut self, currency_effect: CurrencyEffect) -> Self {
+        self.currency_effect = Some(currency_effect);
+        self
+    }
+
+    fn with_stat(mut self, stat_type: StatType, value: i32) -> Self {
+        self.stats.push((stat_type, value));
+        self
+    }
+
+    fn with_implicit(mut self, kind: AffixKind, value: i32) -> Self {
+        self.implicits.push((kind, value));
+        self
+    }
+
+    fn with_damage(self, minimum: i32, maximum: i32) -> Self {
+        self.with_stat(StatType::MinimumDamage, minimum)
+            .with_stat(StatType::MaximumDamage, maximum)
+    }
+
+    /// Attacks per second in hundredths, critical strike chance as a percentage, and reach.
+    fn with_attack(self, attack_speed: i32, critical_chance: i32, range: i32) -> Self {
+        self.with_stat(StatType::AttackSpeed, attack_speed)
+            .with_stat(StatType::CriticalChance, critical_chance)
+            .with_stat(StatType::Range, range)
+    }
+
+    fn only_for(mut self, class: &'static str) -> Self {
+        self.class_restriction = Some(class);
+        self
+    }
+
+    fn stacking_to(mut self, max_stack: u32) -> Self {
+        self.max_stack = max_stack;
+        self
+    }
+
+    pub fn find(name: &str) -> Option<&'static ItemBase> {
+        ITEM_BASES.iter().find(|x| x.name == name)
+    }
+}
+
+static ITEM_BASES: LazyLock<Vec<ItemBase>> = LazyLock::new(|| {
+    let weapon = |t| ItemClass::Equipment(EquipmentType::Weapon(t));
+    let armour = |t| ItemClass::Equipment(EquipmentType::Armour(t));
+    let jewellery = |t| ItemClass::Equipment(EquipmentType::Jewellery(t));
+
+    vec![
+        ItemBase::new("Short Sword", weapon(WeaponType::Sword), 1, 3, 3.0, 8)
+            .with_damage(3, 5)
+            .with_attack(150, 5, 11),
+        ItemBase::new("Claymore", weapon(WeaponType::TwoHandSword), 2, 4, 10.0, 14)
+            .with_damage(9, 17)
+            .with_attack(125, 5, 13)
+            .only_for("Warrior"),
+        ItemBase::new("Kris", weapon(WeaponType::Dagger), 1, 2, 1.0, 7)
+            .with_damage(1, 4)
+            .with_attack(145, 7, 10),
+        ItemBase::new("Hand Axe", weapon(WeaponType::Axe), 1, 3, 4.0, 8)
+            .with_damage(2, 6)
+            .with_attack(140, 5, 11),
+        ItemBase::new("Woodsplitter", weapon(WeaponType::TwoHandAxe), 2, 4, 12.0, 13)
+            .with_damage(8, 20)
+            .with_attack(120, 5, 13),
+        ItemBase::new("Club", weapon(WeaponType::Mace), 1, 3, 5.0, 6)
+            .with_damage(4, 6)
+            .with_attack(135, 5, 11),
+        ItemBase::new("Sledgehammer", weapon(WeaponType::TwoHandMace), 2, 4, 14.0, 12)
+            .with_damage(12, 18)
+            .with_attack(110, 5, 13),
+        ItemBase::new("Short Bow", weapon(WeaponType::Bow), 2, 3, 3.0, 9)
+            .with_damage(3, 8)
+            .with_attack(140, 5, 120),
+        ItemBase::new("Quarterstaff", weapon(WeaponType::Staff), 2, 4, 6.0, 9)
+            .with_damage(6, 12)
+            .with_attack(130, 6, 13),
+        ItemBase::new("Driftwood Wand", weapon(WeaponType::Wand), 1, 3, 1.5, 10)
+            .with_damage(3, 6)
+            .with_attack(140, 8, 60),
+        ItemBase::new("Shako", armour(ArmourType::Helmet), 2, 2, 2.0, 6).with_stat(StatType::Armour, 8),
+        ItemBase::new("Sage's Circlet", armour(ArmourType::Helmet), 2, 2, 1.0, 9)
+            .with_stat(StatType::EnergyShield, 10)
+            .only_for("Sorcerer"),
+        ItemBase::new("Leather Armour", armour(ArmourType::BodyArmour), 2, 3, 8.0, 6).with_stat(StatType::Evasion, 20),
+        ItemBase::new("Plate Vest", armour(ArmourType::BodyArmour), 2, 3, 14.0, 10).with_stat(StatType::Armour, 30),
+        ItemBase::new("Silk Robe", armour(ArmourType::BodyArmour), 2, 3, 4.0, 8).with_stat(StatType::EnergyShield, 18),
+        ItemBase::new("Scale Vest", armour(ArmourType::BodyArmour), 2, 3, 11.0, 9)
+            .with_stat(StatType::Armour, 16)
+            .with_stat(StatType::Evasion, 16),
+        ItemBase::new("Leather Gloves", armour(ArmourType::Gloves), 2, 2, 1.0, 4).with_stat(StatType::Evasion, 5),
+        ItemBase::new("Leather Boots", armour(ArmourType::Boots), 2, 2, 2.0, 4).with_stat(StatType::Evasion, 6),
+        ItemBase::new("Buckler", armour(ArmourType::Shield), 2, 2, 4.0, 5)
+            .with_stat(StatType::Evasion, 10)
+            .with_stat(StatType::BlockChance, 20),
+        ItemBase::new("Tower Shield", armour(ArmourType::Shield), 2, 4, 12.0, 10)
+            .with_stat(StatType::Armour, 25)
+            .with_stat(StatType::BlockChance, 25),
+        ItemBase::new("Leather Belt", jewellery(JewelleryType::Belt), 2, 1, 1.0, 8)
+            .with_implicit(AffixKind::Flat(StatType::Life, ModifierTargetKind::Character), 15),
+        ItemBase::new("Iron Ring", jewellery(JewelleryType::Ring), 1, 1, 0.1, 10)
+            .with_implicit(AffixKind::Flat(StatType::Life, ModifierTargetKind::Character), 10),
+        ItemBase::new("Jade Amulet", jewellery(JewelleryType::Amulet), 1, 1, 0.2, 12)
+            .with_implicit(AffixKind::Flat(StatType::Dexterity, ModifierTargetKind::Character), 12),
+        ItemBase::new("Small Life Flask", ItemClass::Consumable, 1, 2, 0.5, 4)
+            .with_effect(UseEffect::RestoreLife(30))
+            .with_charges(3),
+        ItemBase::new("Health Potion", ItemClass::Consumable, 1, 1, 0.2, 2)
+            .with_effect(UseEffect::RestoreLife(20))
+            .stacking_to(10),
+        ItemBase::new("Tome of Strength", ItemClass::Consumable, 2, 2, 1.0, 20)
+            .with_effect(UseEffect::GainStat(StatType::Strength, 1)),
+        ItemBase::new("Elixir of Might", ItemClass::Consumable, 1, 1, 0.2, 3)
+            .with_effect(UseEffect::ApplyBuff(Buff::new(
+                "Might",
+                StatType::Strength,
+                10,
+                Duration::from_secs(30),
+                StackingRule::Refresh,
+            )))
+            .stacking_to(10),
+        ItemBase::new("Rage Draught", ItemClass::Consumable, 1, 1, 0.2, 3)
+            .with_effect(UseEffect::ApplyBuff(
+                Buff::new("Rage", StatType::IncreasedAttackSpeed, 5, Duration::from_secs(20), StackingRule::Stack)
+                    .stacking_to(5),
+            ))
+            .stacking_to(10),
+        ItemBase::new("Draught of Fortitude", ItemClass::Consumable, 1, 1, 0.2, 3)
+            .with_effect(UseEffect::ApplyBuff(Buff::new(
+                "Fortitude",
+                StatType::Life,
+                25,
+                Duration::from_secs(60),
+                StackingRule::Strongest,
+            )))
+            .stacking_to(10),
+        ItemBase::new("Scroll of Wisdom", ItemClass::Currency, 1, 1, 0.1, 1)
+            .with_currency_effect(CurrencyEffect::Identify)
+            .stacking_to(40),
+        ItemBase::new("Orb of Alchemy", ItemClass::Currency, 1, 1, 0.1, 8)
+            .with_currency_effect(CurrencyEffect::Alchemy)
+            .stacking_to(10),
+        ItemBase::new("Chaos Orb", ItemClass::Currency, 1, 1, 0.1, 12)
+            .with_currency_effect(CurrencyEffect::Chaos)
+            .stacking_to(10),
+        ItemBase::new("Blacksmith's Whetstone", ItemClass::Currency, 1, 1, 0.1, 3)
+            .with_currency_effect(CurrencyEffect::WeaponQuality)
+            .stacking_to(20),
+        ItemBase::new("Armourer's Scrap", ItemClass::Currency, 1, 1, 0.1, 1)
+            .with_currency_effect(CurrencyEffect::ArmourQuality)
+            .stacking_to(40),
+        ItemBase::new("Iron Ore", ItemClass::Material, 1, 1, 1.0, 1).stacking_to(50),
+    ]
+});
@@ -2,6 +2,7 @@ use std::fmt::{Display, Formatter};
 use uuid::{Uuid};
 
 use crate::model::item::{Item, ItemClass, ItemRarity};
+use crate::model::item_base::ItemBase;
 use crate::model::modifier::Modifier;
 use crate::model::requirement::{RequirementBlock, StatRequirement};
 use crate::model::stat::{Stat, StatBlock, StatType};
@@ -20,6 +21,10 @@ pub struct ItemBuilder {
     item_base: Option<String>,
     rarity: ItemRarity,
     item_class: ItemClass,
+    item_level: u32,
+    quantity: u32,
+    unidentified: bool,
+    quality: u32,
     requirements: RequirementBlock,
     base_stats: StatBlock,
     modifiers: Vec<Box<dyn Modifier>>,
@@ -32,6 +37,10 @@ impl Default for ItemBuilder {
             item_base: None,
             rarity: ItemRarity::Normal,
             item_class: ItemClass::None,
+            item_level: 1,
+            quantity: 1,
+            unidentified: false,
+            quality: 0,
             requirements: RequirementBlock::default(),
             base_stats: StatBlock::default(),
             modifiers: Vec::new(),
@@ -64,6 +73,26 @@ impl ItemBuilder {
         self
     }
 
+    pub fn item_level(mut self, item_level: u32) -> Self {
+        self.item_level = item_level;
+        self
+    }
+
+    pub fn quantity(mut self, quantity: u32) -> Self {
+        self.quantity = quantity;
+        self
+    }
+
+    pub fn unidentified(mut self) -> Self {
+        self.unidentified = true;
+        self
+    }
+
+    pub fn quality(mut self, quality: u32) -> Self {
+        self.quality = quality;
+        self
+    }
+
     pub fn with_stat(mut self, stat_type: StatType, value: i32) -> Self {
         self.base_stats.stats.push(Stat{
             stat_type,
@@ -86,20 +115,61 @@ impl ItemBuilder {
     }
 
     pub fn build(self) -> Result<Item, ItemCreationError> {
+        let item_base = match self.item_base {
+            None => return Err(ItemCreationError(String::from("Item Base not specified"))),
+            Some(base) => base,
+        };
+
+        let item_class = match (self.item_class, ItemBase::find(&item_base)) {
+            (ItemClass::None, Some(base)) => base.item_class.clone(),
+            (item_class, _) => item_class,
+        };
+
+        // Items start with their base's stats, except those given values of their own.
+        let mut base_stats = self.base_stats;
+        if let Some(base) = ItemBase::find(&item_base) {
+            for (stat_type, value) in base.stats.iter() {
+                base_stats.add(Stat::new(*stat_type, *value));
+            }
+        }
+
+        let max_stack = ItemBase::find(&item_base).map(|x| x.max_stack).unwrap_or(1);
+        let charges = ItemBase::find(&item_base).and_then(|x| x.max_charges);
+        let durability = ItemBase::find(&item_base).and_then(|x| x.max_durability);
+        let implicits = ItemBase::find(&item_base)
+            .map(|x| x.implicits.iter().map(|(kind, value)| kind.build(*value)).collect())
+            .unwrap_or_default();
+        if self.quantity == 0 || self.quantity > max_stack {
+            return Err(ItemCreationError(format!(
+                "Quantity {} is outside the stack size of {} ({})",
+                self.quantity, item_base, max_stack
+            )));
+        }
+
         let item = Item {
             id: Uuid::new_v4(),
-            item_base: match self.item_base {
-                None => return Err(ItemCreationError(String::from("Item Base not specified"))),
-                Some(base) => base,
-            },
+            item_base,
             name: self.name,
             rarity: self.rarity,
-            item_class: self.item_class,
+            item_class,
+            item_level: self.item_level,
+            quantity: self.quantity,
+            charges,
+            durability,
+            identified: !self.unidentified,
+            quality: self.quality,
             requirements: self.requirements,
-            base_stats: self.base_stats,
+            base_stats,
+            implicits,
             modifiers: self.modifiers,
         };
 
         Ok(item)
     }
 }
+
+/// A plain item of the given catalog base, for tests.
+#[cfg(test)]
+pub(crate) fn item(base: &str) -> Item {
+    Item::builder().base(String::from(base)).build().unwrap()
+}
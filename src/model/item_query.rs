@@ -0,0 +1,316 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::model::item::{Item, ItemRarity};
+use crate::model::modifier::ModifierTarget;
+use crate::model::stat::{Stat, StatBlock, StatType};
+use crate::model::text_match::{matches_class, matches_text, tokenize};
+
+#[derive(Debug)]
+pub struct QueryParseError(String);
+
+impl Display for QueryParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "Query parse error: {}", self.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Field {
+    Rarity,
+    Class,
+    Name,
+    Base,
+    Damage,
+    ItemLevel,
+    Quantity,
+    Stat(StatType),
+    Requirement(StatType),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(super) enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Contains,
+}
+
+impl Operator {
+    /// Splits a leading operator symbol off `s`, longest symbols first.
+    pub(super) fn strip_from(s: &str) -> Option<(Operator, &str)> {
+        [
+            (">=", Operator::GreaterOrEqual),
+            ("<=", Operator::LessOrEqual),
+            ("!=", Operator::NotEqual),
+            ("==", Operator::Equal),
+            (">", Operator::Greater),
+            ("<", Operator::Less),
+            ("=", Operator::Equal),
+            (":", Operator::Equal),
+            ("~", Operator::Contains),
+        ]
+        .iter()
+        .find_map(|(symbol, operator)| s.strip_prefix(symbol).map(|rest| (*operator, rest)))
+    }
+
+    pub(super) fn compare<T: PartialOrd>(&self, a: T, b: T) -> bool {
+        match self {
+            Operator::Equal | Operator::Contains => a == b,
+            Operator::NotEqual => a != b,
+            Operator::Less => a < b,
+            Operator::LessOrEqual => a <= b,
+            Operator::Greater => a > b,
+            Operator::GreaterOrEqual => a >= b,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Number(i32),
+    Rarity(ItemRarity),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+struct QueryTerm {
+    field: Field,
+    operator: Operator,
+    value: Value,
+}
+
+/// A conjunction of search terms such as `rarity:rare class:weapon dmg>=10 "Dexterity">5 name~sword`.
+///
+/// Text fields (`name`, `base`, `class`) accept `:` for an exact, case-insensitive match and
+/// `~` for a substring match. `rarity` and the numeric fields (`dmg`, `ilvl`, `qty`, any stat
+/// name, and `req.<stat>` for requirements) accept `:`, `=`, `!=`, `<`, `<=`, `>` and `>=`.
+/// Stat names containing spaces are quoted.
+#[derive(Debug, Clone)]
+pub struct ItemQuery {
+    terms: Vec<QueryTerm>,
+}
+
+impl ItemQuery {
+    pub fn matches(&self, item: &Item) -> bool {
+        let stats = item.get_derived_stats();
+        self.terms.iter().all(|term| term.matches(item, &stats))
+    }
+}
+
+impl QueryTerm {
+    fn matches(&self, item: &Item, stats: &StatBlock) -> bool {
+        match (&self.field, &self.value) {
+            (Field::Rarity, Value::Rarity(rarity)) => self.operator.compare(item.rarity, *rarity),
+            (Field::Class, Value::Text(text)) => self.match_text(|x| matches_class(&item.item_class, text, x)),
+            (Field::Name, Value::Text(text)) => self.match_text(|x| {
+                matches_text(item.display_name(), text, x) || matches_text(&item.item_base, text, x)
+            }),
+            (Field::Base, Value::Text(text)) => self.match_text(|x| matches_text(&item.item_base, text, x)),
+            (field, Value::Number(number)) => match numeric_value(field, item, stats) {
+                Some(value) => self.operator.compare(value, *number),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Runs `matcher` with whether a substring match was asked for, negating it for `!=`.
+    fn match_text(&self, matcher: impl Fn(bool) -> bool) -> bool {
+        match self.operator {
+            Operator::NotEqual => !matcher(false),
+            Operator::Contains => matcher(true),
+            _ => matcher(false),
+        }
+    }
+}
+
+fn numeric_value(field: &Field, item: &Item, stats: &StatBlock) -> Option<i32> {
+    match field {
+        Field::Damage => {
+            let min = stats.get(StatType::MinimumDamage)?.value;
+            let max = stats.get(StatType::MaximumDamage)?.value;
+            Some((min + max) / 2)
+        }
+        Field::ItemLevel => Some(item.item_level as i32),
+        Field::Quantity => Some(item.quantity as i32),
+        Field::Stat(stat_type) => Some(stat_value(item, stats, *stat_type)),
+        Field::Requirement(stat_type) => item
+            .get_derived_requirements()
+            .requirements
+            .iter()
+            .find(|x| x.stat_type == *stat_type)
+            .map(|x| x.amount),
+        _ => None,
+    }
+}
+
+/// The item's own value for a stat, or what its modifiers grant the character when the
+/// item has no such stat itself.
+fn stat_value(item: &Item, stats: &StatBlock, stat_type: StatType) -> i32 {
+    if let Some(stat) = stats.get(stat_type) {
+        return stat.value;
+    }
+
+    let mut granted = StatBlock {
+        stats: vec![Stat::new(stat_type, 0)],
+    };
+    for modifier in item.active_modifiers() {
+        modifier.apply_to(ModifierTarget::Character(&mut granted));
+    }
+    granted.get(stat_type).map(|x| x.value).unwrap_or(0)
+}
+
+impl FromStr for ItemQuery {
+    type Err = QueryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = tokenize(s)
+            .map_err(QueryParseError)?
+            .iter()
+            .map(|token| parse_term(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ItemQuery { terms })
+    }
+}
+
+fn parse_term(token: &str) -> Result<QueryTerm, QueryParseError> {
+    let (field, rest) = match token.strip_prefix('"') {
+        Some(quoted) => {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            (&quoted[..end], &quoted[(end + 1).min(quoted.len())..])
+        }
+        None => {
+            let end = token
+                .find([':', '~', '=', '!', '<', '>'])
+                .ok_or(QueryParseError(format!("missing operator in '{}'", token)))?;
+            token.split_at(end)
+        }
+    };
+
+    let (operator, value) = Operator::strip_from(rest)
+        .ok_or(QueryParseError(format!("missing operator in '{}'", token)))?;
+
+    let value = value.trim_matches('"');
+    if value.is_empty() {
+        return Err(QueryParseError(format!("missing value in '{}'", token)));
+    }
+
+    let field = parse_field(field)?;
+    let value = match field {
+        Field::Rarity => {
+            if operator == Operator::Contains {
+                return Err(QueryParseError(format!("'{}' can only be compared as a rarity", token)));
+            }
+            Value::Rarity(value.parse().map_err(QueryParseError)?)
+        }
+        Field::Class | Field::Name | Field::Base => {
+            if !matches!(operator, Operator::Equal | Operator::NotEqual | Operator::Contains) {
+                return Err(QueryParseError(format!("'{}' can only be matched as text", token)));
+            }
+            Value::Text(value.to_lowercase())
+        }
+        _ => {
+            if operator == Operator::Contains {
+                return Err(QueryParseError(format!("'{}' can only be compared as a number", token)));
+            }
+            Value::Number(
+                value
+                    .parse()
+                    .map_err(|_| QueryParseError(format!("'{}' is not a number", value)))?,
+            )
+        }
+    };
+
+    Ok(QueryTerm { field, operator, value })
+}
+
+fn parse_field(field: &str) -> Result<Field, QueryParseError> {
+    let stat = |name: &str| name.parse::<StatType>().map_err(QueryParseError);
+
+    match field.to_lowercase().as_str() {
+        "rarity" => Ok(Field::Rarity),
+        "class" | "type" => Ok(Field::Class),
+        "name" => Ok(Field::Name),
+        "base" => Ok(Field::Base),
+        "dmg" | "damage" => Ok(Field::Damage),
+        "ilvl" | "level" => Ok(Field::ItemLevel),
+        "qty" | "quantity" => Ok(Field::Quantity),
+        "str" => Ok(Field::Stat(StatType::Strength)),
+        "dex" => Ok(Field::Stat(StatType::Dexterity)),
+        "int" => Ok(Field::Stat(StatType::Intelligence)),
+        name => match name.strip_prefix("req.") {
+            Some(requirement) => Ok(Field::Requirement(stat(requirement.trim_matches('"'))?)),
+            None => Ok(Field::Stat(stat(name)?)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item_builder::item;
+
+    fn axe() -> Item {
+        Item::builder()
+            .base(String::from("Hand Axe"))
+            .name(String::from("Grim Cleaver"))
+            .rarity(ItemRarity::Rare)
+            .item_level(12)
+            .with_stat(StatType::MinimumDamage, 2)
+            .with_stat(StatType::MaximumDamage, 6)
+            .with_requirement(StatType::Strength, 8)
+            .build()
+            .unwrap()
+    }
+
+    fn matches(query: &str, item: &Item) -> bool {
+        query.parse::<ItemQuery>().unwrap().matches(item)
+    }
+
+    #[test]
+    fn every_term_has_to_match() {
+        let query = "rarity>=magic class:weapon dmg>=4 ilvl<20 req.strength:8";
+        assert!(matches(query, &axe()));
+        assert!(!matches(query, &item("Buckler")));
+        assert!(!matches("rarity>=magic ilvl>12", &axe()));
+        assert!(matches("", &item("Buckler")));
+    }
+
+    #[test]
+    fn text_fields_match_exactly_or_by_substring() {
+        assert!(matches("name:\"grim cleaver\"", &axe()));
+        assert!(matches("name~cleav", &axe()));
+        assert!(matches("name:\"hand axe\"", &axe()));
+        assert!(matches("base!=buckler", &axe()));
+        assert!(!matches("name:grim", &axe()));
+        assert!(matches("class:armour type:shield", &item("Buckler")));
+    }
+
+    #[test]
+    fn quoted_stat_names_may_contain_spaces() {
+        assert!(matches("\"Maximum Damage\">=6", &axe()));
+        assert!(!matches("\"Maximum Damage\">6", &axe()));
+        assert!(!matches("\"Maximum Damage\">0", &item("Buckler")));
+    }
+
+    #[test]
+    fn malformed_terms_are_rejected() {
+        for query in [
+            "name:\"grim",
+            "rare",
+            "rarity:",
+            "rarity:legendary",
+            "rarity~rare",
+            "name>axe",
+            "dmg~5",
+            "dmg>=lots",
+            "sharpness>3",
+        ] {
+            assert!(query.parse::<ItemQuery>().is_err(), "{}", query);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use crate::model::item::Item;
+use crate::model::stat::StatType;
+use crate::model::valuation::Valuation;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    Rarity,
+    Class,
+    Name,
+    ItemLevel,
+    Stat(StatType),
+    /// Estimated value from the standard [`Valuation`].
+    Value,
+}
+
+impl SortKey {
+    /// Orders items the way a player would want them listed: highest rarity, item level
+    /// and stat values first, classes and names in ascending order.
+    pub fn compare(&self, a: &Item, b: &Item) -> Ordering {
+        match self {
+            SortKey::Rarity => b.rarity.cmp(&a.rarity),
+            SortKey::Class => a.item_class.cmp(&b.item_class),
+            SortKey::Name => a.display_name().cmp(b.display_name()),
+            SortKey::ItemLevel => b.item_level.cmp(&a.item_level),
+            SortKey::Stat(stat_type) => {
+                let value = |item: &Item| {
+                    item.get_derived_stats()
+                        .get(*stat_type)
+                        .map(|x| x.value)
+                        .unwrap_or(0)
+                };
+                value(b).cmp(&value(a))
+            }
+            SortKey::Value => {
+                let valuation = Valuation::standard();
+                valuation.appraise(b).score.total_cmp(&valuation.appraise(a).score)
+            }
+        }
+    }
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortKey::Rarity => write!(f, "Rarity"),
+            SortKey::Class => write!(f, "Class"),
+            SortKey::Name => write!(f, "Name"),
+            SortKey::ItemLevel => write!(f, "Item Level"),
+            SortKey::Stat(stat_type) => write!(f, "{}", stat_type),
+            SortKey::Value => write!(f, "Value"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::inventory::Inventory;
+    use crate::model::item::ItemRarity;
+    use crate::model::item_builder;
+
+    fn item(base: &str, rarity: ItemRarity, item_level: u32) -> Item {
+        let mut item = item_builder::item(base);
+        item.rarity = rarity;
+        item.item_level = item_level;
+        item
+    }
+
+    fn sorted(key: SortKey) -> Vec<String> {
+        let mut inventory = Inventory::new();
+        inventory.add(item("Iron Ring", ItemRarity::Magic, 5)).unwrap();
+        inventory.add(item("Buckler", ItemRarity::Normal, 12)).unwrap();
+        inventory.add(item("Hand Axe", ItemRarity::Rare, 1)).unwrap();
+        inventory.sort_by(key);
+        inventory.iter().map(|x| x.item_base.clone()).collect()
+    }
+
+    #[test]
+    fn rarity_item_level_and_stats_sort_highest_first() {
+        assert_eq!(sorted(SortKey::Rarity), ["Hand Axe", "Iron Ring", "Buckler"]);
+        assert_eq!(sorted(SortKey::ItemLevel), ["Buckler", "Iron Ring", "Hand Axe"]);
+        assert_eq!(sorted(SortKey::Stat(StatType::BlockChance)), ["Buckler", "Iron Ring", "Hand Axe"]);
+    }
+
+    #[test]
+    fn names_sort_in_ascending_order() {
+        assert_eq!(sorted(SortKey::Name), ["Buckler", "Hand Axe", "Iron Ring"]);
+    }
+
+    #[test]
+    fn groups_follow_class_order_and_keep_inventory_order() {
+        let mut inventory = Inventory::new();
+        for base in ["Iron Ring", "Hand Axe", "Jade Amulet", "Iron Ring"] {
+            inventory.add(item(base, ItemRarity::Normal, 1)).unwrap();
+        }
+
+        let groups: Vec<(String, usize)> = inventory
+            .group_by_class()
+            .into_iter()
+            .map(|(class, items)| (class.to_string(), items.len()))
+            .collect();
+        assert_eq!(groups, [(String::from("Axe"), 1), (String::from("Ring"), 2), (String::from("Amulet"), 1)]);
+    }
+}
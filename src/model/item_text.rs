@@ -0,0 +1,403 @@
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+use crate::model::item::{Item, ItemRarity};
+use crate::model::item_base::ItemBase;
+use crate::model::modifier::{
+    BasicStatModifier, CompositeStatModifier, FlatStatModifier, FrontStatModifier, Modifier, ModifierKind,
+    ModifierPass, ModifierTargetKind, RequirementModifier,
+};
+use crate::model::requirement::{RequirementBlock, StatRequirement};
+use crate::model::stat::{Stat, StatBlock, StatType};
+
+const SEPARATOR: &str = "--------";
+const IMPLICIT: &str = "(implicit)";
+/// Marks a modifier that affects the item itself rather than the character.
+const LOCAL: &str = "(local)";
+
+#[derive(Debug)]
+pub struct ItemTextError(String);
+
+impl Display for ItemTextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "Item text error: {}", self.0)
+    }
+}
+
+impl Item {
+    /// Renders the item as the plain text block players share, with sections split by
+    /// dashed lines:
+    ///
+    /// ```text
+    /// Item Class: Dagger
+    /// Rarity: Rare
+    /// Death's Kiss
+    /// Kris
+    /// --------
+    /// Minimum Damage: 1
+    /// Maximum Damage: 4
+    /// --------
+    /// Requirements:
+    /// Dexterity: 15
+    /// --------
+    /// Item Level: 1
+    /// --------
+    /// +35% Increased Attack Speed
+    /// ```
+    ///
+    /// Stats and requirements are the item's own, before its modifiers apply. Modifiers that
+    /// affect the item rather than the character end in `(local)`. Implicit modifiers get a
+    /// section of their own before the explicit ones, each line ending in `(implicit)`.
+    /// Unidentified items are refused, as the text could not carry their hidden modifiers
+    /// without revealing them.
+    pub fn to_text(&self) -> Result<String, ItemTextError> {
+        if !self.identified {
+            return Err(ItemTextError(format!("{} is unidentified", self.item_base)));
+        }
+
+        let mut sections = Vec::new();
+
+        let mut header = vec![
+            format!("Item Class: {}", self.item_class),
+            format!("Rarity: {:?}", self.rarity),
+        ];
+        if let Some(name) = &self.name {
+            header.push(name.clone());
+        }
+        header.push(self.item_base.clone());
+        sections.push(header);
+
+        let mut properties = Vec::new();
+        if self.max_stack() > 1 {
+            properties.push(format!("Stack Size: {}/{}", self.quantity, self.max_stack()));
+        }
+        if let (Some(charges), Some(max_charges)) = (self.charges, self.max_charges()) {
+            properties.push(format!("Charges: {}/{}", charges, max_charges));
+        }
+        if let (Some(durability), Some(max_durability)) = (self.durability, self.max_durability()) {
+            properties.push(format!("Durability: {}/{}", durability, max_durability));
+        }
+        if self.quality > 0 {
+            properties.push(format!("Quality: +{}%", self.quality));
+        }
+        for stat in self.base_stats.stats.iter() {
+            properties.push(format!("{}: {}", stat.stat_type, stat.stat_type.format_value(stat.value)));
+        }
+        sections.push(properties);
+
+        if !self.requirements.requirements.is_empty() {
+            let mut requirements = vec![String::from("Requirements:")];
+            for requirement in self.requirements.requirements.iter() {
+                requirements.push(format!(
+                    "{}: {}",
+                    requirement.stat_type,
+                    requirement.stat_type.format_value(requirement.amount)
+                ));
+            }
+            sections.push(requirements);
+        }
+
+        sections.push(vec![format!("Item Level: {}", self.item_level)]);
+        sections.push(self.implicits.iter().map(|x| format!("{} {}", modifier_line(x.as_ref()), IMPLICIT)).collect());
+        sections.push(self.modifiers.iter().map(|x| modifier_line(x.as_ref())).collect());
+
+        Ok(sections
+            .into_iter()
+            .filter(|x| !x.is_empty())
+            .map(|x| x.join("\n"))
+            .collect::<Vec<_>>()
+            .join(&format!("\n{}\n", SEPARATOR)))
+    }
+
+    /// Rebuilds an item from the block written by [`Item::to_text`]. The base must be in the
+    /// catalog, and modifiers are recognised from their descriptions. A modifier affects the
+    /// item when it is marked `(local)`, and the character otherwise.
+    pub fn from_text(s: &str) -> Result<Item, ItemTextError> {
+        let mut sections = s
+            .split(SEPARATOR)
+            .map(|x| x.lines().map(str::trim).filter(|x| !x.is_empty()).collect::<Vec<_>>())
+            .filter(|x| !x.is_empty());
+
+        let header = sections.next().ok_or(ItemTextError(String::from("empty item")))?;
+        let mut names = Vec::new();
+        let mut rarity = ItemRarity::Normal;
+        let mut item_class = None;
+        for line in header {
+            if let Some(value) = line.strip_prefix("Rarity:") {
+                rarity = value.trim().parse().map_err(ItemTextError)?;
+            } else if let Some(value) = line.strip_prefix("Item Class:") {
+                item_class = Some(value.trim().parse().map_err(ItemTextError)?);
+            } else {
+                names.push(line.to_string());
+            }
+        }
+
+        let (name, item_base) = match &names[..] {
+            [base] => (None, base.clone()),
+            [name, base] => (Some(name.clone()), base.clone()),
+            _ => return Err(ItemTextError(String::from("expected a name and a base, or just a base"))),
+        };
+        let base = ItemBase::find(&item_base).ok_or(ItemTextError(format!("unknown base '{}'", item_base)))?;
+
+        let mut item = Item {
+            id: Uuid::new_v4(),
+            item_base,
+            name,
+            rarity,
+            item_class: item_class.unwrap_or(base.item_class.clone()),
+            item_level: 1,
+            quantity: 1,
+            charges: base.max_charges,
+            durability: base.max_durability,
+            identified: true,
+            quality: 0,
+            requirements: RequirementBlock::default(),
+            base_stats: StatBlock::default(),
+            implicits: Vec::new(),
+            modifiers: Vec::new(),
+        };
+
+        for section in sections {
+            if section[0] == "Requirements:" {
+                for line in &section[1..] {
+                    let (stat_type, amount) = parse_property(line)?;
+                    item.requirements.requirements.push(StatRequirement { stat_type, amount });
+                }
+            } else if section.iter().all(|x| x.ends_with(IMPLICIT)) {
+                for line in section {
+                    let description = line.trim_end_matches(IMPLICIT).trim_end();
+                    item.implicits.push(parse_modifier(description)?);
+                }
+            } else if section == ["Unidentified"] {
+                return Err(ItemTextError(String::from("unidentified items cannot be imported")));
+            } else if let Some(value) = section[0].strip_prefix("Item Level:") {
+                if let Some(line) = section.get(1) {
+                    return Err(ItemTextError(format!("unexpected '{}' after the item level", line)));
+                }
+                item.item_level = parse_number(value.trim())?;
+            } else if section
+                .iter()
+                .all(|x| x.starts_with("Stack Size:") || x.starts_with("Charges:") || x.starts_with("Durability:") || x.starts_with("Quality:") || parse_property(x).is_ok())
+            {
+                for line in section {
+                    if let Some(value) = line.strip_prefix("Stack Size:") {
+                        item.quantity = parse_number(value.trim().split('/').next().unwrap_or_default())?;
+                    } else if let Some(value) = line.strip_prefix("Charges:") {
+                        item.charges = Some(parse_number(value.trim().split('/').next().unwrap_or_default())?);
+                    } else if let Some(value) = line.strip_prefix("Quality:") {
+                        let value = value.trim().trim_start_matches('+').trim_end_matches('%');
+                        item.quality = parse_number(value)?;
+                    } else if let Some(value) = line.strip_prefix("Durability:") {
+                        item.durability = Some(parse_number(value.trim().split('/').next().unwrap_or_default())?);
+                    } else {
+                        let (stat_type, value) = parse_property(line)?;
+                        item.base_stats.add(Stat::new(stat_type, value));
+                    }
+                }
+            } else {
+                for line in section {
+                    let modifier = parse_modifier(line)?;
+                    item.modifiers.push(modifier);
+                }
+            }
+        }
+
+        if item.quantity == 0 || item.quantity > base.max_stack {
+            return Err(ItemTextError(format!("quantity {} does not fit a stack of {}", item.quantity, base.max_stack)));
+        }
+        Ok(item)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, ItemTextError> {
+    s.parse().map_err(|_| ItemTextError(format!("'{}' is not a number", s)))
+}
+
+fn modifier_line(modifier: &dyn Modifier) -> String {
+    match modifier.target() {
+        ModifierTargetKind::Item => format!("{} {}", modifier.description(), LOCAL),
+        _ => modifier.description(),
+    }
+}
+
+/// Reads a `Stat Name: value` line, with the value as [`StatType::format_value`] writes it.
+fn parse_property(line: &str) -> Result<(StatType, i32), ItemTextError> {
+    let (name, value) = line
+        .split_once(':')
+        .ok_or(ItemTextError(format!("expected 'Stat: value' in '{}'", line)))?;
+    let stat_type: StatType = name.parse().map_err(ItemTextError)?;
+    Ok((stat_type, stat_type.parse_value(value.trim()).map_err(ItemTextError)?))
+}
+
+/// Reads a `+value Stat` or `+value% Stat` part of a description, returning the value and
+/// whether it is a percentage. Composite descriptions put the `%` before the value.
+fn parse_value(part: &str) -> Result<(i32, bool, StatType), ItemTextError> {
+    let (value, stat) = part
+        .split_once(' ')
+        .ok_or(ItemTextError(format!("expected a value and a stat in '{}'", part)))?;
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let (value, percent) = match value.strip_prefix('%').or(value.strip_suffix('%')) {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    Ok((parse_number(value)?, percent, stat.parse().map_err(ItemTextError)?))
+}
+
+fn parse_modifier(line: &str) -> Result<Box<dyn Modifier>, ItemTextError> {
+    let (line, target) = match line.strip_suffix(LOCAL) {
+        Some(line) => (line.trim_end(), ModifierTargetKind::Item),
+        None => (line, ModifierTargetKind::Character),
+    };
+
+    let requirement_name = StatType::Requirements.to_string();
+    if let Some(value) = line.strip_suffix(&format!(" {}", requirement_name)) {
+        let (value, direction) = value
+            .split_once("% ")
+            .ok_or(ItemTextError(format!("unknown modifier '{}'", line)))?;
+        let value: i32 = parse_number(value)?;
+        return match direction {
+            "Increased" => Ok(Box::new(RequirementModifier { value })),
+            "Reduced" => Ok(Box::new(RequirementModifier { value: -value })),
+            _ => Err(ItemTextError(format!("unknown modifier '{}'", line))),
+        };
+    }
+
+    if line.contains(", ") {
+        let parts = line.split(", ").map(parse_value).collect::<Result<Vec<_>, _>>()?;
+        let percent = parts[0].1;
+        return Ok(Box::new(CompositeStatModifier {
+            values: parts.iter().map(|x| x.0).collect(),
+            stats: parts.iter().map(|x| x.2).collect(),
+            modifier_kind: if percent { ModifierKind::Percent } else { ModifierKind::Flat },
+            modifier_pass: if percent { ModifierPass::Increased } else { ModifierPass::Flat },
+            target,
+        }));
+    }
+
+    let (value, percent, stat) = parse_value(line)?;
+    let modifier: Box<dyn Modifier> = match (stat, percent) {
+        (StatType::IncreasedDamage, true) => Box::new(FrontStatModifier {
+            front: stat,
+            value,
+            stats: vec![StatType::MinimumDamage, StatType::MaximumDamage],
+            modifier_kind: ModifierKind::Percent,
+            modifier_pass: ModifierPass::Increased,
+            target,
+        }),
+        (StatType::IncreasedDefences, true) => Box::new(FrontStatModifier {
+            front: stat,
+            value,
+            stats: vec![StatType::Armour, StatType::Evasion, StatType::EnergyShield],
+            modifier_kind: ModifierKind::Percent,
+            modifier_pass: ModifierPass::Increased,
+            target,
+        }),
+        (_, true) => Box::new(BasicStatModifier {
+            value,
+            stat,
+            modifier_kind: ModifierKind::Percent,
+            modifier_pass: ModifierPass::Increased,
+            target,
+        }),
+        (_, false) => Box::new(FlatStatModifier {
+            value,
+            stat,
+            target,
+        }),
+    };
+    Ok(modifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn damage(value: i32, target: ModifierTargetKind) -> FrontStatModifier {
+        FrontStatModifier {
+            front: StatType::IncreasedDamage,
+            stats: vec![StatType::MinimumDamage, StatType::MaximumDamage],
+            modifier_kind: ModifierKind::Percent,
+            modifier_pass: ModifierPass::Increased,
+            value,
+            target,
+        }
+    }
+
+    fn rare_sword() -> Item {
+        Item::builder()
+            .base(String::from("Short Sword"))
+            .name(String::from("Death's Kiss"))
+            .rarity(ItemRarity::Rare)
+            .item_level(12)
+            .quality(8)
+            .with_requirement(StatType::Strength, 15)
+            .with_modifier(damage(80, ModifierTargetKind::Item))
+            .with_modifier(damage(10, ModifierTargetKind::Character))
+            .with_modifier(FlatStatModifier { value: 5, stat: StatType::Dexterity, target: ModifierTargetKind::Character })
+            .with_modifier(RequirementModifier { value: -20 })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn export_then_import_keeps_the_item() {
+        let item = rare_sword();
+        let text = item.to_text().unwrap();
+        let loaded = Item::from_text(&text).unwrap();
+
+        assert_eq!(loaded.name.as_deref(), Some("Death's Kiss"));
+        assert_eq!(loaded.item_level, 12);
+        assert_eq!(loaded.quality, 8);
+        assert_eq!(loaded.to_text().unwrap(), text);
+        let targets: Vec<ModifierTargetKind> = loaded.modifiers.iter().map(|x| x.target()).collect();
+        assert_eq!(
+            targets,
+            [ModifierTargetKind::Item, ModifierTargetKind::Character, ModifierTargetKind::Character, ModifierTargetKind::Requirements]
+        );
+    }
+
+    #[test]
+    fn stats_are_written_as_they_are_shown() {
+        let text = rare_sword().to_text().unwrap();
+        assert!(text.contains("Attacks per Second: 1."), "{}", text);
+        assert!(text.contains("Critical Chance: 5%"), "{}", text);
+        assert!(text.contains("+80% Increased Damage (local)"), "{}", text);
+        assert!(text.lines().any(|x| x == "+10% Increased Damage"), "{}", text);
+
+        let loaded = Item::from_text(&text).unwrap();
+        let base = rare_sword();
+        for stat_type in [StatType::AttackSpeed, StatType::CriticalChance] {
+            assert_eq!(loaded.base_stats.get(stat_type).map(|x| x.value), base.base_stats.get(stat_type).map(|x| x.value));
+        }
+    }
+
+    #[test]
+    fn unidentified_items_are_refused() {
+        let item = Item::builder()
+            .base(String::from("Leather Gloves"))
+            .name(String::from("Dusk Ward"))
+            .rarity(ItemRarity::Rare)
+            .unidentified()
+            .build()
+            .unwrap();
+        assert!(item.to_text().is_err());
+        assert!(Item::from_text("Rarity: Rare\nLeather Gloves\n--------\nUnidentified").is_err());
+    }
+
+    #[test]
+    fn malformed_text_is_rejected() {
+        for text in [
+            "",
+            "Rarity: Rare\nNo Such Base",
+            "Rarity: Shiny\nShort Sword",
+            "Rarity: Rare\nOne\nTwo\nShort Sword",
+            "Short Sword\n--------\nItem Level: twelve",
+            "Short Sword\n--------\nItem Level: 12\n+5 Strength",
+            "Short Sword\n--------\nAttacks per Second: fast",
+            "Short Sword\n--------\n+5 Luck",
+            "Health Potion\n--------\nStack Size: 99/10",
+        ] {
+            assert!(Item::from_text(text).is_err(), "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::model::item::{Item, ItemRarity};
+use crate::model::item_query::Operator;
+use crate::model::text_match::{matches_class, matches_text, tokenize};
+
+const DEFAULT_FILTER: &str = include_str!("../../filters/default.filter");
+
+#[derive(Debug)]
+pub struct LootFilterError(String);
+
+impl Display for LootFilterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "Loot filter error: {}", self.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FilterColour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// How an item should be displayed, as decided by the first matching filter block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct ItemStyle {
+    pub hidden: bool,
+    pub text_colour: Option<FilterColour>,
+    pub border_colour: Option<FilterColour>,
+    pub background_colour: Option<FilterColour>,
+}
+
+#[derive(Debug, Clone)]
+enum FilterCondition {
+    Rarity(Operator, ItemRarity),
+    Class(Vec<String>),
+    BaseType(Vec<String>),
+    HasMod(Vec<String>),
+    ModCount(Operator, i32),
+    ItemLevel(Operator, i32),
+}
+
+impl FilterCondition {
+    fn matches(&self, item: &Item) -> bool {
+        match self {
+            FilterCondition::Rarity(operator, rarity) => operator.compare(item.rarity, *rarity),
+            FilterCondition::Class(names) => names.iter().any(|x| matches_class(&item.item_class, x, true)),
+            FilterCondition::BaseType(names) => names.iter().any(|x| matches_text(&item.item_base, x, true)),
+            FilterCondition::HasMod(names) => names.iter().any(|x| {
+                item.active_modifiers().any(|modifier| matches_text(&modifier.description(), x, true))
+            }),
+            FilterCondition::ModCount(operator, count) => operator.compare(item.known_modifiers().len() as i32, *count),
+            FilterCondition::ItemLevel(operator, level) => operator.compare(item.item_level as i32, *level),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FilterBlock {
+    conditions: Vec<FilterCondition>,
+    style: ItemStyle,
+}
+
+/// An ordered list of `Show` and `Hide` blocks read from a rule file, in the spirit of
+/// ARPG loot filters:
+///
+/// ```text
+/// Show
+///     Rarity >= Rare
+///     Class "Weapon"
+///     SetTextColor 255 255 119
+///     SetBorderColor 255 0 0
+/// ```
+///
+/// Conditions are `Rarity`, `Class`, `BaseType`, `HasMod` (matched against modifier
+/// descriptions), `ModCount` and `ItemLevel`; text conditions take one or more values and
+/// match any of them as a case-insensitive substring. Actions are `SetTextColor`,
+/// `SetBorderColor` and `SetBackgroundColor`. Lines starting with `#` are comments.
+#[derive(Debug, Clone)]
+pub struct LootFilter {
+    blocks: Vec<FilterBlock>,
+}
+
+impl LootFilter {
+    pub fn load(path: impl AsRef<Path>) -> Result<LootFilter, LootFilterError> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .map_err(|e| LootFilterError(format!("could not read {}: {}", path.display(), e)))?
+            .parse()
+    }
+
+    /// The style of the first block matching the item, or an unstyled, shown item when none do.
+    pub fn style_for(&self, item: &Item) -> ItemStyle {
+        self.blocks
+            .iter()
+            .find(|block| block.conditions.iter().all(|x| x.matches(item)))
+            .map(|block| block.style)
+            .unwrap_or_default()
+    }
+}
+
+impl Default for LootFilter {
+    fn default() -> Self {
+        DEFAULT_FILTER.parse().expect("the default loot filter is valid")
+    }
+}
+
+impl FromStr for LootFilter {
+    type Err = LootFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut blocks: Vec<FilterBlock> = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let at_line = |e: LootFilterError| LootFilterError(format!("line {}: {}", index + 1, e.0));
+            let tokens: Vec<String> = tokenize(line)
+                .map_err(LootFilterError)
+                .map_err(at_line)?
+                .into_iter()
+                .map(|x| x.trim_matches('"').to_string())
+                .collect();
+            let (keyword, args) = tokens.split_first().expect("line is not empty");
+
+            match keyword.as_str() {
+                "Show" | "Hide" => blocks.push(FilterBlock {
+                    conditions: Vec::new(),
+                    style: ItemStyle {
+                        hidden: keyword == "Hide",
+                        ..ItemStyle::default()
+                    },
+                }),
+                _ => {
+                    let Some(block) = blocks.last_mut() else {
+                        return Err(at_line(LootFilterError(format!("'{}' outside of a Show or Hide block", keyword))));
+                    };
+                    parse_rule(block, keyword, args).map_err(at_line)?;
+                }
+            }
+        }
+
+        Ok(LootFilter { blocks })
+    }
+}
+
+fn parse_rule(block: &mut FilterBlock, keyword: &str, args: &[String]) -> Result<(), LootFilterError> {
+    let values = || {
+        if args.is_empty() {
+            return Err(LootFilterError(format!("{} needs at least one value", keyword)));
+        }
+        Ok(args.iter().map(|x| x.to_lowercase()).collect())
+    };
+
+    match keyword {
+        "Rarity" => {
+            let (operator, value) = parse_comparison(keyword, args)?;
+            let rarity = value.parse().map_err(LootFilterError)?;
+            block.conditions.push(FilterCondition::Rarity(operator, rarity));
+        }
+        "Class" => block.conditions.push(FilterCondition::Class(values()?)),
+        "BaseType" => block.conditions.push(FilterCondition::BaseType(values()?)),
+        "HasMod" => block.conditions.push(FilterCondition::HasMod(values()?)),
+        "ModCount" => {
+            let (operator, value) = parse_comparison(keyword, args)?;
+            block.conditions.push(FilterCondition::ModCount(operator, parse_number(value)?));
+        }
+        "ItemLevel" => {
+            let (operator, value) = parse_comparison(keyword, args)?;
+            block.conditions.push(FilterCondition::ItemLevel(operator, parse_number(value)?));
+        }
+        "SetTextColor" => block.style.text_colour = Some(parse_colour(args)?),
+        "SetBorderColor" => block.style.border_colour = Some(parse_colour(args)?),
+        "SetBackgroundColor" => block.style.background_colour = Some(parse_colour(args)?),
+        _ => return Err(LootFilterError(format!("unknown rule '{}'", keyword))),
+    }
+    Ok(())
+}
+
+/// Reads `[operator] value`, where a missing operator means equality.
+fn parse_comparison<'a>(keyword: &str, args: &'a [String]) -> Result<(Operator, &'a str), LootFilterError> {
+    match args {
+        [value] => Ok((Operator::Equal, value)),
+        [operator, value] => match Operator::strip_from(operator) {
+            Some((operator, "")) if operator != Operator::Contains => Ok((operator, value)),
+            _ => Err(LootFilterError(format!("unknown operator '{}'", operator))),
+        },
+        _ => Err(LootFilterError(format!("{} expects an optional operator and a value", keyword))),
+    }
+}
+
+fn parse_number(value: &str) -> Result<i32, LootFilterError> {
+    value
+        .parse()
+        .map_err(|_| LootFilterError(format!("'{}' is not a number", value)))
+}
+
+fn parse_colour(args: &[String]) -> Result<FilterColour, LootFilterError> {
+    let channels = args
+        .iter()
+        .map(|x| {
+            x.parse::<u8>()
+                .map_err(|_| LootFilterError(format!("'{}' is not a colour channel between 0 and 255", x)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match channels[..] {
+        [r, g, b] | [r, g, b, _] => Ok(FilterColour { r, g, b }),
+        _ => Err(LootFilterError(String::from("colours are given as red, green and blue values"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item_builder::item;
+
+    const FILTER: &str = "\
+# Hide low level junk.
+Hide
+    ItemLevel < 5
+    Rarity <= Magic
+
+Show
+    BaseType \"Iron Ring\" \"Jade Amulet\"
+    SetTextColor 255 255 119 255
+
+Show
+    Class Weapon
+    SetBorderColor 255 0 0
+";
+
+    fn at_level(base: &str, item_level: u32) -> Item {
+        let mut item = item(base);
+        item.item_level = item_level;
+        item
+    }
+
+    #[test]
+    fn the_first_matching_block_decides_the_style() {
+        let filter: LootFilter = FILTER.parse().unwrap();
+
+        assert!(filter.style_for(&at_level("Iron Ring", 1)).hidden);
+        let ring = filter.style_for(&at_level("Iron Ring", 10));
+        assert!(!ring.hidden);
+        assert_eq!(ring.text_colour, Some(FilterColour { r: 255, g: 255, b: 119 }));
+        assert_eq!(ring.border_colour, None);
+        assert_eq!(filter.style_for(&at_level("Hand Axe", 10)).border_colour, Some(FilterColour { r: 255, g: 0, b: 0 }));
+        assert_eq!(filter.style_for(&at_level("Buckler", 10)), ItemStyle::default());
+    }
+
+    #[test]
+    fn the_default_filter_is_valid() {
+        LootFilter::default();
+    }
+
+    #[test]
+    fn errors_name_the_offending_line() {
+        let error = "Show\n    Rarity Rare\n    Sparkle 3\n".parse::<LootFilter>().unwrap_err();
+        assert_eq!(error.to_string(), "Loot filter error: line 3: unknown rule 'Sparkle'");
+    }
+
+    #[test]
+    fn malformed_rules_are_rejected() {
+        for filter in [
+            "Rarity Rare",
+            "Show\n    Rarity Legendary",
+            "Show\n    Rarity ~ Rare",
+            "Show\n    ItemLevel >= 5 10",
+            "Show\n    ModCount > few",
+            "Show\n    Class",
+            "Show\n    BaseType \"Iron Ring",
+            "Show\n    SetTextColor 255 255",
+            "Show\n    SetTextColor 256 0 0",
+        ] {
+            assert!(filter.parse::<LootFilter>().is_err(), "{}", filter);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::ops::RangeInclusive;
+use std::sync::LazyLock;
+
+use crate::model::affix;
+use crate::model::item::{Item, ItemClass, ItemRarity};
+use crate::model::item_base::ItemBase;
+use crate::model::random::Rng;
+
+#[derive(Debug, Clone)]
+pub struct LootEntry {
+    pub base: &'static str,
+    pub weight: u32,
+    pub quantity: RangeInclusive<u32>,
+}
+
+/// Weighted bases and rarities that items are rolled from.
+#[derive(Debug, Clone)]
+pub struct LootTable {
+    pub name: &'static str,
+    pub entries: Vec<LootEntry>,
+    /// Chances of each rarity for equipment. Other items are always normal.
+    pub rarities: Vec<(ItemRarity, u32)>,
+}
+
+impl LootTable {
+    pub fn find(name: &str) -> Option<&'static LootTable> {
+        LOOT_TABLES.iter().find(|x| x.name == name)
+    }
+
+    pub fn roll(&self, rng: &mut Rng, item_level: u32) -> Option<Item> {
+        let entry = rng.weighted(&self.entries, |x| x.weight)?;
+        let base = ItemBase::find(entry.base)?;
+
+        let rarity = match base.item_class {
+            ItemClass::Equipment(_) => rng.weighted(&self.rarities, |x| x.1).map(|x| x.0).unwrap_or(ItemRarity::Normal),
+            _ => ItemRarity::Normal,
+        };
+        let quantity = rng.range(*entry.quantity.start() as i32..=*entry.quantity.end() as i32) as u32;
+
+        let mut builder = Item::builder()
+            .base(base.name.clone())
+            .rarity(rarity)
+            .item_level(item_level)
+            .quantity(quantity.clamp(1, base.max_stack));
+        // Magic and rare drops arrive with their modifiers hidden.
+        if matches!(rarity, ItemRarity::Magic | ItemRarity::Rare) {
+            builder = builder.unidentified();
+        }
+        let mut item = builder.build().ok()?;
+        affix::roll_affixes(&mut item, rng);
+        Some(item)
+    }
+}
+
+fn entry(base: &'static str, weight: u32) -> LootEntry {
+    LootEntry {
+        base,
+        weight,
+        quantity: 1..=1,
+    }
+}
+
+fn stack(base: &'static str, weight: u32, quantity: RangeInclusive<u32>) -> LootEntry {
+    LootEntry { base, weight, quantity }
+}
+
+static LOOT_TABLES: LazyLock<Vec<LootTable>> = LazyLock::new(|| {
+    let equipment_rarities = vec![(ItemRarity::Normal, 60), (ItemRarity::Magic, 30), (ItemRarity::Rare, 10)];
+
+    vec![
+        LootTable {
+            name: "Blacksmith",
+            entries: vec![
+                entry("Short Sword", 20),
+                entry("Claymore", 10),
+                entry("Kris", 15),
+                entry("Hand Axe", 20),
+                entry("Woodsplitter", 10),
+                entry("Club", 15),
+                entry("Sledgehammer", 10),
+                entry("Short Bow", 15),
+                entry("Quarterstaff", 10),
+                entry("Driftwood Wand", 10),
+                entry("Shako", 10),
+                entry("Sage's Circlet", 5),
+                entry("Leather Armour", 15),
+                entry("Plate Vest", 10),
+                entry("Silk Robe", 10),
+                entry("Scale Vest", 10),
+                entry("Leather Gloves", 15),
+                entry("Leather Boots", 15),
+                entry("Buckler", 10),
+                entry("Tower Shield", 5),
+                entry("Leather Belt", 10),
+                entry("Iron Ring", 10),
+                entry("Jade Amulet", 5),
+            ],
+            rarities: equipment_rarities.clone(),
+        },
+        LootTable {
+            name: "Alchemist",
+            entries: vec![
+                entry("Small Life Flask", 20),
+                stack("Health Potion", 30, 2..=5),
+                stack("Elixir of Might", 10, 1..=3),
+                stack("Rage Draught", 10, 1..=3),
+                stack("Draught of Fortitude", 10, 1..=3),
+                entry("Tome of Strength", 2),
+                stack("Scroll of Wisdom", 20, 5..=20),
+                stack("Orb of Alchemy", 5, 1..=2),
+                stack("Chaos Orb", 3, 1..=2),
+                stack("Blacksmith's Whetstone", 10, 1..=3),
+                stack("Armourer's Scrap", 10, 1..=5),
+            ],
+            rarities: equipment_rarities,
+        },
+    ]
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::integrity;
+
+    #[test]
+    fn every_roll_is_a_legal_item() {
+        let mut rng = Rng::seeded(7);
+        for table in LOOT_TABLES.iter() {
+            for _ in 0..200 {
+                let item = table.roll(&mut rng, 20).unwrap();
+                let entry = table.entries.iter().find(|x| x.base == item.item_base).unwrap();
+                assert!(entry.quantity.contains(&item.quantity) || item.quantity == item.max_stack(), "{}", item.item_base);
+                assert_eq!(item.identified, !matches!(item.rarity, ItemRarity::Magic | ItemRarity::Rare));
+                assert!(integrity::check_item(&item).is_empty(), "{}", item.item_base);
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_seed_rolls_the_same_items() {
+        let table = LootTable::find("Blacksmith").unwrap();
+        let roll = |seed| {
+            let mut rng = Rng::seeded(seed);
+            (0..10).map(|_| table.roll(&mut rng, 20).map(|x| (x.item_base, x.rarity))).collect::<Vec<_>>()
+        };
+        assert_eq!(roll(42), roll(42));
+    }
+}
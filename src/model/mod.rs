@@ -3,7 +3,31 @@ pub mod modifier;
 pub mod stat;
 pub mod requirement;
 pub mod item_builder;
+pub mod item_base;
+pub mod consumable;
+pub mod currency;
+pub mod buff;
+pub mod experience;
+pub mod passive;
+pub mod character_class;
+pub mod random;
+pub mod affix;
+pub mod loot_table;
+pub mod vendor;
+pub mod valuation;
+pub mod item_sort;
+pub mod item_query;
+pub mod text_match;
+pub mod item_text;
+pub mod loot_filter;
 pub mod player;
 pub mod inventory;
+pub mod equipment;
+pub mod comparison;
+pub mod optimizer;
+pub mod stash;
+pub mod profile;
+pub mod save;
+pub mod integrity;
 
 
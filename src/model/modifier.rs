@@ -3,11 +3,19 @@ use std::fmt::Debug;
 use crate::model::requirement::RequirementBlock;
 use crate::model::stat::{StatBlock, StatType};
 
-pub trait Modifier {
+/// Modifiers are `Send + Sync` so shared data like the passive tree can hold them in statics.
+pub trait Modifier: Send + Sync {
     fn apply_to(&self, target: ModifierTarget);
     fn pass(&self) -> &ModifierPass;
+    /// Whether the modifier adds its values to its stats or scales them by a percentage.
+    fn kind(&self) -> &ModifierKind;
     fn description(&self) -> String;
     fn get_affected_stat(&self) -> StatType;
+    fn target(&self) -> ModifierTargetKind;
+    /// The numbers the modifier applies, one per affected stat.
+    fn values(&self) -> Vec<i32>;
+    /// A single-line form of the modifier that [`decode`] reads back, used by character saves.
+    fn encode(&self) -> String;
 }
 
 impl Debug for Box<dyn Modifier> {
@@ -17,18 +25,20 @@ impl Debug for Box<dyn Modifier> {
 }
 
 
+#[derive(Debug)]
 pub enum ModifierKind {
     Flat,
     Percent,
 }
 
+#[derive(Debug)]
 pub enum ModifierPass {
     Flat,
     Increased,
     Requirements,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ModifierTargetKind {
     Character,
     Item,
@@ -104,6 +114,10 @@ impl Modifier for FlatStatModifier {
         &ModifierPass::Flat
     }
 
+    fn kind(&self) -> &ModifierKind {
+        &ModifierKind::Flat
+    }
+
     fn description(&self) -> String {
         format!("+{} {}", self.value, self.stat)
     }
@@ -111,6 +125,18 @@ impl Modifier for FlatStatModifier {
     fn get_affected_stat(&self) -> StatType {
         self.stat
     }
+
+    fn target(&self) -> ModifierTargetKind {
+        self.target
+    }
+
+    fn values(&self) -> Vec<i32> {
+        vec![self.value]
+    }
+
+    fn encode(&self) -> String {
+        format!("flat {} {:?} {:?}", self.value, self.stat, self.target)
+    }
 }
 pub struct BasicStatModifier {
     pub value: i32,
@@ -144,6 +170,10 @@ impl Modifier for BasicStatModifier {
         &self.modifier_pass
     }
 
+    fn kind(&self) -> &ModifierKind {
+        &self.modifier_kind
+    }
+
     fn description(&self) -> String {
         match self.modifier_kind {
             ModifierKind::Flat => format!("+{} {}", self.value, self.stat),
@@ -154,6 +184,21 @@ impl Modifier for BasicStatModifier {
     fn get_affected_stat(&self) -> StatType {
         self.stat
     }
+
+    fn target(&self) -> ModifierTargetKind {
+        self.target
+    }
+
+    fn values(&self) -> Vec<i32> {
+        vec![self.value]
+    }
+
+    fn encode(&self) -> String {
+        format!(
+            "basic {} {:?} {:?} {:?} {:?}",
+            self.value, self.stat, self.modifier_kind, self.modifier_pass, self.target
+        )
+    }
 }
 
 pub struct FrontStatModifier {
@@ -192,6 +237,10 @@ impl Modifier for FrontStatModifier {
         &self.modifier_pass
     }
 
+    fn kind(&self) -> &ModifierKind {
+        &self.modifier_kind
+    }
+
     fn description(&self) -> String {
         match self.modifier_kind {
             ModifierKind::Flat => format!("+{} {}", self.value, self.front),
@@ -202,6 +251,26 @@ impl Modifier for FrontStatModifier {
     fn get_affected_stat(&self) -> StatType {
         self.front
     }
+
+    fn target(&self) -> ModifierTargetKind {
+        self.target
+    }
+
+    fn values(&self) -> Vec<i32> {
+        vec![self.value]
+    }
+
+    fn encode(&self) -> String {
+        format!(
+            "front {:?} {} {} {:?} {:?} {:?}",
+            self.front,
+            self.value,
+            encode_list(&self.stats, |x| format!("{:?}", x)),
+            self.modifier_kind,
+            self.modifier_pass,
+            self.target
+        )
+    }
 }
 
 pub struct CompositeStatModifier {
@@ -239,6 +308,10 @@ impl Modifier for CompositeStatModifier {
         &self.modifier_pass
     }
 
+    fn kind(&self) -> &ModifierKind {
+        &self.modifier_kind
+    }
+
     fn description(&self) -> String {
         let mut res = String::new();
         for (index, stat) in self.stats.iter().enumerate() {
@@ -256,6 +329,25 @@ impl Modifier for CompositeStatModifier {
     fn get_affected_stat(&self) -> StatType {
         self.stats[0]
     }
+
+    fn target(&self) -> ModifierTargetKind {
+        self.target
+    }
+
+    fn values(&self) -> Vec<i32> {
+        self.values.clone()
+    }
+
+    fn encode(&self) -> String {
+        format!(
+            "composite {} {} {:?} {:?} {:?}",
+            encode_list(&self.values, |x| x.to_string()),
+            encode_list(&self.stats, |x| format!("{:?}", x)),
+            self.modifier_kind,
+            self.modifier_pass,
+            self.target
+        )
+    }
 }
 
 
@@ -276,6 +368,10 @@ impl Modifier for RequirementModifier {
         &ModifierPass::Requirements
     }
 
+    fn kind(&self) -> &ModifierKind {
+        &ModifierKind::Percent
+    }
+
     fn description(&self) -> String {
         format!(
             "{}% {} {}",
@@ -292,4 +388,93 @@ impl Modifier for RequirementModifier {
     fn get_affected_stat(&self) -> StatType {
         StatType::Requirements
     }
+
+    fn target(&self) -> ModifierTargetKind {
+        ModifierTargetKind::Requirements
+    }
+
+    fn values(&self) -> Vec<i32> {
+        vec![self.value]
+    }
+
+    fn encode(&self) -> String {
+        format!("requirement {}", self.value)
+    }
+}
+
+fn encode_list<T>(values: &[T], encode: impl Fn(&T) -> String) -> String {
+    values.iter().map(encode).collect::<Vec<_>>().join(",")
+}
+
+/// Rebuilds a modifier from the form written by [`Modifier::encode`].
+pub fn decode(s: &str) -> Result<Box<dyn Modifier>, String> {
+    let parts: Vec<&str> = s.split_whitespace().collect();
+    let number = |x: &str| x.parse::<i32>().map_err(|_| format!("'{}' is not a number", x));
+    let stat = |x: &str| x.parse::<StatType>();
+    let list = |x: &str| x.split(',').map(stat).collect::<Result<Vec<_>, _>>();
+
+    match parts[..] {
+        ["flat", value, stat_type, target] => Ok(Box::new(FlatStatModifier {
+            value: number(value)?,
+            stat: stat(stat_type)?,
+            target: decode_target(target)?,
+        })),
+        ["basic", value, stat_type, kind, pass, target] => Ok(Box::new(BasicStatModifier {
+            value: number(value)?,
+            stat: stat(stat_type)?,
+            modifier_kind: decode_kind(kind)?,
+            modifier_pass: decode_pass(pass)?,
+            target: decode_target(target)?,
+        })),
+        ["front", front, value, stats, kind, pass, target] => Ok(Box::new(FrontStatModifier {
+            front: stat(front)?,
+            value: number(value)?,
+            stats: list(stats)?,
+            modifier_kind: decode_kind(kind)?,
+            modifier_pass: decode_pass(pass)?,
+            target: decode_target(target)?,
+        })),
+        ["composite", values, stats, kind, pass, target] => {
+            let values = values.split(',').map(number).collect::<Result<Vec<_>, _>>()?;
+            let stats = list(stats)?;
+            if values.is_empty() || values.len() != stats.len() {
+                return Err(format!("Mismatched values and stats in modifier: {}", s));
+            }
+            Ok(Box::new(CompositeStatModifier {
+                values,
+                stats,
+                modifier_kind: decode_kind(kind)?,
+                modifier_pass: decode_pass(pass)?,
+                target: decode_target(target)?,
+            }))
+        }
+        ["requirement", value] => Ok(Box::new(RequirementModifier { value: number(value)? })),
+        _ => Err(format!("Unknown modifier: {}", s)),
+    }
+}
+
+fn decode_kind(s: &str) -> Result<ModifierKind, String> {
+    match s {
+        "Flat" => Ok(ModifierKind::Flat),
+        "Percent" => Ok(ModifierKind::Percent),
+        _ => Err(format!("Unknown modifier kind: {}", s)),
+    }
+}
+
+fn decode_pass(s: &str) -> Result<ModifierPass, String> {
+    match s {
+        "Flat" => Ok(ModifierPass::Flat),
+        "Increased" => Ok(ModifierPass::Increased),
+        "Requirements" => Ok(ModifierPass::Requirements),
+        _ => Err(format!("Unknown modifier pass: {}", s)),
+    }
+}
+
+fn decode_target(s: &str) -> Result<ModifierTargetKind, String> {
+    match s {
+        "Character" => Ok(ModifierTargetKind::Character),
+        "Item" => Ok(ModifierTargetKind::Item),
+        "Requirements" => Ok(ModifierTargetKind::Requirements),
+        _ => Err(format!("Unknown modifier target: {}", s)),
+    }
 }
@@ -0,0 +1,268 @@
+use strum::IntoEnumIterator;
+
+use crate::model::equipment::EquipmentSlot;
+use crate::model::item::Item;
+use crate::model::modifier::ModifierTargetKind;
+use crate::model::player::Player;
+use crate::model::stat::{StatBlock, StatType};
+
+#[derive(Debug, Copy, Clone)]
+pub enum OptimizationTarget {
+    Damage,
+    Stat(StatType),
+}
+
+impl OptimizationTarget {
+    pub fn score(&self, stats: &StatBlock) -> f32 {
+        let value = |stat_type| stats.get(stat_type).map(|s| s.value).unwrap_or(0) as f32;
+
+        match self {
+            OptimizationTarget::Damage => {
+                let average = (value(StatType::MinimumDamage) + value(StatType::MaximumDamage)) / 2.0;
+                // Attacks per second are kept in hundredths; anything without a speed attacks once.
+                let attack_speed = stats.get(StatType::AttackSpeed).map(|s| s.value as f32 / 100.0).unwrap_or(1.0);
+                average * attack_speed * (1.0 + value(StatType::IncreasedAttackSpeed) / 100.0)
+            }
+            OptimizationTarget::Stat(stat_type) => value(*stat_type),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GearSet<'a> {
+    pub items: Vec<(EquipmentSlot, &'a Item)>,
+    pub score: f32,
+}
+
+/// The hands are searched first so that, once they are settled, no later slot can change
+/// which weapon the damage and attack speed come from.
+const HAND_SLOTS: [EquipmentSlot; 2] = [EquipmentSlot::Weapon, EquipmentSlot::OffHand];
+
+/// Finds the highest scoring legal combination of the player's inventory and equipped items.
+///
+/// A set is legal when its items can be equipped one after the other with each item's
+/// requirements met by the stats granted so far, so an item's attribute bonus can enable
+/// another item in the same set. Every slot tries each of its candidates and nothing, and a
+/// branch is cut as soon as its hands do not fit, an item in it could not have its
+/// requirements met even with every remaining candidate equipped, or equipping every
+/// remaining candidate would still not beat the best set found. Those last two cuts assume
+/// gear never lowers a stat, so they are skipped if any candidate does. Ties keep the
+/// equipped gear.
+pub fn optimize<'a>(player: &'a Player, target: OptimizationTarget) -> GearSet<'a> {
+    let slots = HAND_SLOTS
+        .into_iter()
+        .chain(EquipmentSlot::iter().filter(|x| !HAND_SLOTS.contains(x)));
+    let candidates: Vec<(EquipmentSlot, Vec<&Item>)> = slots
+        .map(|slot| {
+            let items = player
+                .inventory
+                .iter()
+                .chain(player.equippement.iter())
+                .filter(|x| slot.accepts(&x.item_class) && player.can_use(x))
+                .collect();
+            (slot, items)
+        })
+        .collect();
+
+    let equipped: Vec<(EquipmentSlot, &Item)> = EquipmentSlot::iter()
+        .filter_map(|slot| player.equipped_in(slot).map(|x| (slot, x)))
+        .collect();
+    let start = match fits_hands(&equipped) && is_legal(player, &equipped) {
+        true => equipped,
+        false => Vec::new(),
+    };
+    let mut best = GearSet {
+        score: score_of(player, target, &start),
+        items: start,
+    };
+
+    let search = Search {
+        player,
+        target,
+        prune: candidates.iter().flat_map(|(_, x)| x.iter()).all(|x| never_lowers_stats(x)),
+        candidates,
+    };
+    search.extend(&mut Vec::new(), 0, &mut best);
+    best.items.sort_by_key(|(slot, _)| *slot);
+    best
+}
+
+struct Search<'a> {
+    player: &'a Player,
+    target: OptimizationTarget,
+    /// Each slot with the items that can go in it, hands first.
+    candidates: Vec<(EquipmentSlot, Vec<&'a Item>)>,
+    /// Whether branches may be cut by trying every remaining candidate at once.
+    prune: bool,
+}
+
+impl<'a> Search<'a> {
+    /// Tries every way to fill the slots from `depth` on alongside `chosen`, keeping the
+    /// highest scoring legal set in `best`.
+    fn extend(&self, chosen: &mut Vec<(EquipmentSlot, &'a Item)>, depth: usize, best: &mut GearSet<'a>) {
+        if !fits_hands(chosen) || (self.prune && !self.can_improve(chosen, depth, best.score)) {
+            return;
+        }
+
+        let Some((slot, items)) = self.candidates.get(depth) else {
+            if is_legal(self.player, chosen) {
+                let score = score_of(self.player, self.target, chosen);
+                if score > best.score {
+                    *best = GearSet { items: chosen.clone(), score };
+                }
+            }
+            return;
+        };
+
+        self.extend(chosen, depth + 1, best);
+        for item in items.iter() {
+            // A one-handed weapon is a candidate for both hands.
+            if chosen.iter().any(|(_, x)| x.id == item.id) {
+                continue;
+            }
+            chosen.push((*slot, item));
+            self.extend(chosen, depth + 1, best);
+            chosen.pop();
+        }
+    }
+
+    /// Whether `chosen` could still grow into a legal set scoring above `best`, judged by
+    /// equipping it together with every candidate for the slots from `depth` on. More gear
+    /// only raises stats, so those stats are the most any completion can reach.
+    fn can_improve(&self, chosen: &[(EquipmentSlot, &'a Item)], depth: usize, best: f32) -> bool {
+        let remaining = self.candidates[depth..]
+            .iter()
+            .flat_map(|(_, x)| x.iter().copied())
+            .filter(|x| chosen.iter().all(|(_, item)| item.id != x.id));
+        let stats = self.player.derive_stats(chosen.iter().map(|(_, x)| *x).chain(remaining));
+
+        if !chosen.iter().all(|(_, x)| x.get_derived_requirements().is_met_by(&stats)) {
+            return false;
+        }
+        // Until the hands are settled an off hand weapon could lend the bound its damage.
+        depth < HAND_SLOTS.len() || self.target.score(&stats) > best
+    }
+}
+
+/// Whether the item has no negative stat and no modifier taking a stat down.
+fn never_lowers_stats(item: &Item) -> bool {
+    item.get_derived_stats().stats.iter().all(|x| x.value >= 0)
+        && item
+            .active_modifiers()
+            .filter(|x| x.target() != ModifierTargetKind::Requirements)
+            .all(|x| x.values().iter().all(|value| *value >= 0))
+}
+
+fn score_of(player: &Player, target: OptimizationTarget, set: &[(EquipmentSlot, &Item)]) -> f32 {
+    target.score(&player.derive_stats(set.iter().map(|(_, x)| *x)))
+}
+
+/// Only a second weapon goes in the off hand, and never beside a two-handed weapon.
+fn fits_hands(set: &[(EquipmentSlot, &Item)]) -> bool {
+    let in_slot = |slot| set.iter().find(|(x, _)| *x == slot).map(|(_, x)| *x);
+    match (in_slot(EquipmentSlot::Weapon), in_slot(EquipmentSlot::OffHand)) {
+        (Some(main), Some(_)) if main.is_two_handed() => false,
+        (main, Some(off)) if off.weapon_type().is_some() => main.is_some_and(|x| x.weapon_type().is_some()),
+        _ => true,
+    }
+}
+
+fn is_legal(player: &Player, chosen: &[(EquipmentSlot, &Item)]) -> bool {
+    let mut equipped: Vec<&Item> = Vec::new();
+    let mut remaining: Vec<&Item> = chosen.iter().map(|(_, x)| *x).collect();
+
+    while !remaining.is_empty() {
+        let stats = player.derive_stats(equipped.iter().copied());
+        let Some(index) = remaining
+            .iter()
+            .position(|x| x.get_derived_requirements().is_met_by(&stats))
+        else {
+            return false;
+        };
+        equipped.push(remaining.remove(index));
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::character_class::CharacterClass;
+    use crate::model::item_builder::item;
+    use crate::model::modifier::FlatStatModifier;
+
+    fn warrior() -> Player {
+        Player::new(CharacterClass::find("Warrior").unwrap())
+    }
+
+    fn chosen<'a>(set: &GearSet<'a>, slot: EquipmentSlot) -> Option<&'a Item> {
+        set.items.iter().find(|(x, _)| *x == slot).map(|(_, x)| *x)
+    }
+
+    #[test]
+    fn ties_keep_the_equipped_gear() {
+        let mut player = warrior();
+        player.pickup(item("Hand Axe")).unwrap();
+
+        let set = optimize(&player, OptimizationTarget::Damage);
+        let equipped = player.equipped_in(EquipmentSlot::Weapon).unwrap();
+        assert_eq!(chosen(&set, EquipmentSlot::Weapon).map(|x| x.id), Some(equipped.id));
+    }
+
+    #[test]
+    fn two_handed_weapons_leave_the_off_hand_empty() {
+        let mut player = warrior();
+        player.pickup(item("Claymore")).unwrap();
+        player.pickup(item("Tower Shield")).unwrap();
+
+        let damage = optimize(&player, OptimizationTarget::Damage);
+        assert_eq!(chosen(&damage, EquipmentSlot::Weapon).unwrap().item_base, "Claymore");
+        assert!(chosen(&damage, EquipmentSlot::OffHand).is_none());
+
+        let armour = optimize(&player, OptimizationTarget::Stat(StatType::Armour));
+        assert_eq!(chosen(&armour, EquipmentSlot::OffHand).unwrap().item_base, "Tower Shield");
+        assert!(!chosen(&armour, EquipmentSlot::Weapon).is_some_and(|x| x.is_two_handed()));
+    }
+
+    #[test]
+    fn an_item_can_meet_the_requirements_of_another() {
+        let mut player = warrior();
+        let strength = player.get_derived_stats().get(StatType::Strength).unwrap().value;
+        let claymore = Item::builder()
+            .base(String::from("Claymore"))
+            .with_requirement(StatType::Strength, strength + 10)
+            .build()
+            .unwrap();
+        let ring = Item::builder()
+            .base(String::from("Iron Ring"))
+            .with_modifier(FlatStatModifier { value: 10, stat: StatType::Strength, target: ModifierTargetKind::Character })
+            .build()
+            .unwrap();
+        player.pickup(claymore).unwrap();
+        player.pickup(ring).unwrap();
+
+        let set = optimize(&player, OptimizationTarget::Damage);
+        assert_eq!(chosen(&set, EquipmentSlot::Weapon).unwrap().item_base, "Claymore");
+        assert_eq!(chosen(&set, EquipmentSlot::Ring).unwrap().item_base, "Iron Ring");
+    }
+
+    #[test]
+    fn both_hands_can_change_together() {
+        let mut player = warrior();
+        player.equip(item("Claymore")).unwrap();
+        let flat = |stat| FlatStatModifier { value: 10, stat, target: ModifierTargetKind::Character };
+        let buckler = Item::builder()
+            .base(String::from("Buckler"))
+            .with_modifier(flat(StatType::MinimumDamage))
+            .with_modifier(flat(StatType::MaximumDamage))
+            .build()
+            .unwrap();
+        player.pickup(item("Club")).unwrap();
+        player.pickup(buckler).unwrap();
+
+        let set = optimize(&player, OptimizationTarget::Damage);
+        assert_eq!(chosen(&set, EquipmentSlot::Weapon).unwrap().item_base, "Club");
+        assert_eq!(chosen(&set, EquipmentSlot::OffHand).unwrap().item_base, "Buckler");
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::model::text_match::tokenize;
+use crate::model::modifier::{self, Modifier};
+
+const STANDARD_TREE: &str = include_str!("../../data/passive.tree");
+
+/// Passive points earned for every level after the first.
+pub const PASSIVE_POINTS_PER_LEVEL: u32 = 1;
+
+static PASSIVE_TREE: LazyLock<PassiveTree> =
+    LazyLock::new(|| STANDARD_TREE.parse().expect("the standard passive tree is valid"));
+
+#[derive(Debug)]
+pub struct PassiveTreeError(String);
+
+impl Display for PassiveTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "Passive tree error: {}", self.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct PassiveNode {
+    pub id: String,
+    pub name: String,
+    /// The class that begins at this node, if it is a starting node.
+    pub start: Option<String>,
+    pub modifiers: Vec<Box<dyn Modifier>>,
+    /// Ids of the adjacent nodes, in both directions.
+    pub links: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct PassiveTree {
+    nodes: Vec<PassiveNode>,
+}
+
+impl PassiveTree {
+    /// The tree every character allocates into, read from `data/passive.tree`.
+    pub fn standard() -> &'static PassiveTree {
+        &PASSIVE_TREE
+    }
+
+    pub fn nodes(&self) -> &[PassiveNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, id: &str) -> Option<&PassiveNode> {
+        self.nodes.iter().find(|x| x.id == id)
+    }
+
+    pub fn start_for(&self, class: &str) -> Option<&PassiveNode> {
+        self.nodes.iter().find(|x| x.start.as_deref() == Some(class))
+    }
+
+    /// A node can be allocated when it is not a starting node, is not yet allocated, and
+    /// is linked to the class's starting node or to an allocated node.
+    pub fn can_allocate(&self, class: &str, allocated: &[String], id: &str) -> bool {
+        let Some(node) = self.node(id) else {
+            return false;
+        };
+        if node.start.is_some() || allocated.iter().any(|x| x == id) {
+            return false;
+        }
+
+        let start = self.start_for(class).map(|x| x.id.as_str());
+        node.links
+            .iter()
+            .any(|x| Some(x.as_str()) == start || allocated.contains(x))
+    }
+
+    /// A node can be refunded when every other allocated node stays connected to the
+    /// class's starting node without it.
+    pub fn can_refund(&self, class: &str, allocated: &[String], id: &str) -> bool {
+        if !allocated.iter().any(|x| x == id) {
+            return false;
+        }
+        let Some(start) = self.start_for(class) else {
+            return false;
+        };
+
+        let remaining: HashSet<&str> = allocated.iter().map(|x| x.as_str()).filter(|x| *x != id).collect();
+        let mut reached = HashSet::from([start.id.as_str()]);
+        let mut frontier = vec![start.id.as_str()];
+        while let Some(current) = frontier.pop() {
+            let Some(node) = self.node(current) else {
+                continue;
+            };
+            for link in node.links.iter().map(|x| x.as_str()) {
+                if remaining.contains(link) && reached.insert(link) {
+                    frontier.push(link);
+                }
+            }
+        }
+        remaining.iter().all(|x| reached.contains(x))
+    }
+
+    /// Adds `from` to the links of every node `from` links to, so links declared on one
+    /// side work in both directions.
+    fn link_both_ways(&mut self) -> Result<(), PassiveTreeError> {
+        let mut pairs = Vec::new();
+        for node in self.nodes.iter() {
+            for link in node.links.iter() {
+                if self.node(link).is_none() {
+                    return Err(PassiveTreeError(format!("'{}' links to unknown node '{}'", node.id, link)));
+                }
+                pairs.push((link.clone(), node.id.clone()));
+            }
+        }
+
+        for (id, link) in pairs {
+            let node = self.nodes.iter_mut().find(|x| x.id == id).expect("links were checked");
+            if !node.links.contains(&link) {
+                node.links.push(link);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PassiveTree {
+    type Err = PassiveTreeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tree = PassiveTree { nodes: Vec::new() };
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let at_line = |e: PassiveTreeError| PassiveTreeError(format!("line {}: {}", index + 1, e.0));
+            let tokens: Vec<String> = tokenize(line)
+                .map_err(PassiveTreeError)
+                .map_err(at_line)?
+                .into_iter()
+                .map(|x| x.trim_matches('"').to_string())
+                .collect();
+            let (keyword, args) = tokens.split_first().expect("line is not empty");
+
+            if keyword == "Node" {
+                let [id] = args else {
+                    return Err(at_line(PassiveTreeError(String::from("Node needs exactly one id"))));
+                };
+                if tree.node(id).is_some() {
+                    return Err(at_line(PassiveTreeError(format!("duplicate node '{}'", id))));
+                }
+                tree.nodes.push(PassiveNode {
+                    id: id.clone(),
+                    name: id.clone(),
+                    start: None,
+                    modifiers: Vec::new(),
+                    links: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some(node) = tree.nodes.last_mut() else {
+                return Err(at_line(PassiveTreeError(format!("'{}' outside of a Node block", keyword))));
+            };
+            match (keyword.as_str(), args) {
+                ("Name", [name]) => node.name = name.clone(),
+                ("Start", [class]) => node.start = Some(class.clone()),
+                ("Link", links) if !links.is_empty() => node.links.extend(links.iter().cloned()),
+                ("Modifier", _) => {
+                    let encoded = line.strip_prefix("Modifier").unwrap_or_default();
+                    let modifier = modifier::decode(encoded).map_err(|e| at_line(PassiveTreeError(e)))?;
+                    node.modifiers.push(modifier);
+                }
+                _ => return Err(at_line(PassiveTreeError(format!("unexpected '{}'", line)))),
+            }
+        }
+
+        tree.link_both_ways()?;
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TREE: &str = "\
+Node start
+    Start Warrior
+    Link a
+
+Node a
+    Name \"Might\"
+    Modifier flat 10 Strength Character
+    Link b
+
+Node b
+";
+
+    fn allocated(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn nodes_are_linked_both_ways() {
+        let tree: PassiveTree = TREE.parse().unwrap();
+        assert_eq!(tree.node("a").unwrap().name, "Might");
+        assert_eq!(tree.node("a").unwrap().modifiers.len(), 1);
+        assert_eq!(tree.node("b").unwrap().name, "b");
+        assert_eq!(tree.node("b").unwrap().links, ["a"]);
+        assert_eq!(tree.start_for("Warrior").map(|x| x.id.as_str()), Some("start"));
+    }
+
+    #[test]
+    fn allocation_grows_from_the_starting_node() {
+        let tree: PassiveTree = TREE.parse().unwrap();
+        assert!(tree.can_allocate("Warrior", &[], "a"));
+        assert!(!tree.can_allocate("Warrior", &[], "b"));
+        assert!(!tree.can_allocate("Warrior", &[], "start"));
+        assert!(!tree.can_allocate("Ranger", &[], "a"));
+        assert!(tree.can_allocate("Warrior", &allocated(&["a"]), "b"));
+        assert!(!tree.can_allocate("Warrior", &allocated(&["a"]), "a"));
+    }
+
+    #[test]
+    fn refunds_cannot_cut_off_other_nodes() {
+        let tree: PassiveTree = TREE.parse().unwrap();
+        let both = allocated(&["a", "b"]);
+        assert!(!tree.can_refund("Warrior", &both, "a"));
+        assert!(tree.can_refund("Warrior", &both, "b"));
+        assert!(!tree.can_refund("Warrior", &allocated(&["a"]), "b"));
+    }
+
+    #[test]
+    fn the_standard_tree_is_valid() {
+        assert!(PassiveTree::standard().start_for("Warrior").is_some());
+    }
+
+    #[test]
+    fn malformed_trees_are_rejected() {
+        for tree in [
+            "Name \"Orphan\"",
+            "Node",
+            "Node a b",
+            "Node a\nNode a",
+            "Node a\n    Link",
+            "Node a\n    Link nowhere",
+            "Node a\n    Modifier flat lots Strength Character",
+            "Node a\n    Glow brightly",
+            "Node a\n    Name \"Unclosed",
+        ] {
+            assert!(tree.parse::<PassiveTree>().is_err(), "{}", tree);
+        }
+    }
+}
@@ -1,50 +1,687 @@
-use crate::model::inventory::Inventory;
+use uuid::Uuid;
+
+use crate::model::buff::{self, ActiveEffect, Buff};
+use crate::model::character_class::CharacterClass;
+use crate::model::comparison::{EquipmentComparison, RequirementChange};
+use crate::model::equipment::{Equipment, EquipmentSlot};
+use crate::model::experience::{ATTRIBUTE_POINTS_PER_LEVEL, ExperienceCurve, LevelUp};
+use crate::model::inventory::{Inventory, InventoryError, InventoryErrorReason};
 use crate::model::item::Item;
+use crate::model::item_base::ItemBase;
 use crate::model::modifier::ModifierTarget::Character;
-use crate::model::stat::StatBlock;
+use crate::model::passive::{PASSIVE_POINTS_PER_LEVEL, PassiveTree};
+use crate::model::random::Rng;
+use crate::model::stash::{restore, take_whole};
+use crate::model::stat::{Stat, StatBlock, StatType};
+use crate::model::vendor;
+use std::time::Duration;
+
+const BASE_CARRY_WEIGHT: f32 = 50.0;
+const CARRY_WEIGHT_PER_STRENGTH: f32 = 2.0;
+const MAX_BLOCK_CHANCE: i32 = 75;
+/// Most separate items, counting a stack as one, a character can carry.
+pub const MAX_CARRIED_ITEMS: usize = 30;
 
 pub struct Player {
     pub base_stats: StatBlock,
     pub inventory: Inventory,
-    pub equippement: Inventory,
+    pub equippement: Equipment,
+    /// Current life, out of [`Player::max_life`].
+    pub life: i32,
+    pub effects: Vec<ActiveEffect>,
+    /// Total experience earned, which sets the level through an [`ExperienceCurve`].
+    pub experience: u64,
+    pub attribute_points: u32,
+    /// Names the class's starting node in the passive tree.
+    pub class: String,
+    /// Ids of the allocated passive nodes, not counting the class's starting node.
+    pub passives: Vec<String>,
+    pub gold: u32,
 }
 
 impl Player {
-    pub fn equip(&mut self, item: Item) {
-        self.equippement.add(item);
+    /// A new level 1 character of `class`, at full life, wearing and carrying the class's
+    /// starting items.
+    pub fn new(class: &CharacterClass) -> Player {
+        let mut inventory = Inventory::with_grid(10, 4);
+        inventory.max_slots = Some(MAX_CARRIED_ITEMS);
+        let mut player = Player {
+            base_stats: class.starting_stats(),
+            inventory,
+            equippement: Equipment::new(),
+            life: 0,
+            effects: Vec::new(),
+            experience: 0,
+            attribute_points: 0,
+            class: String::from(class.name),
+            passives: Vec::new(),
+            gold: 0,
+        };
+
+        for starting_item in class.starting_items.iter() {
+            let item = starting_item.build();
+            if EquipmentSlot::for_class(&item.item_class).is_some() {
+                player.equip(item).expect("starting items suit their class");
+            } else {
+                player.pickup(item).expect("starting items fit a new inventory");
+            }
+        }
+
+        player.life = player.max_life();
+        player
+    }
+
+    /// Whether the item's base allows the player's class to equip it.
+    pub fn can_use(&self, item: &Item) -> bool {
+        ItemBase::find(&item.item_base)
+            .and_then(|x| x.class_restriction)
+            .is_none_or(|x| x == self.class)
+    }
+
+    /// Equips the item, returning whatever it replaced, or gives the item back when the
+    /// player's class cannot use it. A two-handed weapon replaces the items in both hands.
+    pub fn equip(&mut self, item: Item) -> Result<Vec<Item>, Box<Item>> {
+        let replaced = self.put_on(item)?;
+        Ok(replaced.into_iter().map(|x| x.1).collect())
+    }
+
+    /// Equips an item from the inventory, putting whatever it replaces in its place. Nothing
+    /// changes if the item cannot be equipped or the replaced items cannot be carried.
+    pub fn equip_from_inventory(&mut self, id: Uuid) -> bool {
+        let Some((item, position)) = take_whole(&mut self.inventory, id) else {
+            return false;
+        };
+        let replaced = match self.put_on(item) {
+            Ok(replaced) => replaced,
+            Err(item) => {
+                restore(&mut self.inventory, *item, position);
+                return false;
+            }
+        };
+
+        let mut picked_up: Vec<(EquipmentSlot, Uuid)> = Vec::new();
+        let mut replaced = replaced.into_iter();
+        while let Some((slot, item)) = replaced.next() {
+            let replaced_id = item.id;
+            let Err(e) = self.pickup(item) else {
+                picked_up.push((slot, replaced_id));
+                continue;
+            };
+
+            // Put everything back as it was: the replaced items on, the item in its place.
+            let slot_taken = self.equippement.slot_of(id).expect("the item was just equipped");
+            let item = self.equippement.remove(slot_taken).expect("the item was just equipped");
+            self.equippement.insert(slot, *e.item);
+            for (slot, item) in replaced {
+                self.equippement.insert(slot, item);
+            }
+            for (slot, replaced_id) in picked_up {
+                let (replaced, _) = take_whole(&mut self.inventory, replaced_id).expect("the item was just picked up");
+                self.equippement.insert(slot, replaced);
+            }
+            restore(&mut self.inventory, item, position);
+            return false;
+        }
+        true
+    }
+
+    /// Takes the item in the slot off and puts it in the inventory. It stays equipped if it
+    /// cannot be carried.
+    pub fn unequip(&mut self, slot: EquipmentSlot) -> bool {
+        let Some(item) = self.equippement.remove(slot) else {
+            return false;
+        };
+        match self.pickup(item) {
+            Ok(()) => true,
+            Err(e) => {
+                self.equippement.insert(slot, *e.item);
+                false
+            }
+        }
+    }
+
+    /// Puts the item in the slot [`Player::target_slot`] picks, returning the items it took
+    /// off with the slots they were in.
+    fn put_on(&mut self, item: Item) -> Result<Vec<(EquipmentSlot, Item)>, Box<Item>> {
+        let Some(slot) = self.target_slot(&item) else {
+            return Err(Box::new(item));
+        };
+        if !self.can_use(&item) {
+            return Err(Box::new(item));
+        }
+
+        let displaced: Vec<EquipmentSlot> = self
+            .displaced_by(&item, slot)
+            .iter()
+            .filter_map(|x| self.equippement.slot_of(x.id))
+            .collect();
+        let replaced = displaced
+            .into_iter()
+            .filter_map(|x| self.equippement.remove(x).map(|item| (x, item)))
+            .collect();
+        self.equippement.insert(slot, item);
+        Ok(replaced)
+    }
+
+    pub fn equipped_in(&self, slot: EquipmentSlot) -> Option<&Item> {
+        self.equippement.get(slot)
+    }
+
+    /// Where equipping the item would put it. A one-handed weapon goes in the off hand when
+    /// the main hand holds another one-handed weapon and the off hand is free.
+    fn target_slot(&self, item: &Item) -> Option<EquipmentSlot> {
+        let slot = EquipmentSlot::for_class(&item.item_class)?;
+        let dual_wields = item.is_one_handed_weapon()
+            && self
+                .equipped_in(EquipmentSlot::Weapon)
+                .is_some_and(|x| x.is_one_handed_weapon() && x.id != item.id)
+            && self.equipped_in(EquipmentSlot::OffHand).is_none();
+        Some(if dual_wields { EquipmentSlot::OffHand } else { slot })
+    }
+
+    /// The equipped items that putting `item` in `slot` takes off: whatever is in the slot,
+    /// plus the other hand's item when either of them is a two-handed weapon.
+    fn displaced_by(&self, item: &Item, slot: EquipmentSlot) -> Vec<&Item> {
+        self.equippement
+            .slots()
+            .filter(|(_, x)| x.id != item.id)
+            .filter(|(other, x)| {
+                *other == slot
+                    || (item.is_two_handed() && *other == EquipmentSlot::OffHand)
+                    || (slot == EquipmentSlot::OffHand && x.is_two_handed())
+            })
+            .map(|(_, x)| x)
+            .collect()
+    }
+
+    /// Carry weight granted by the player's Strength.
+    pub fn weight_limit(&self) -> f32 {
+        let strength = self
+            .get_derived_stats()
+            .get(StatType::Strength)
+            .map(|x| x.value)
+            .unwrap_or(0);
+        BASE_CARRY_WEIGHT + strength as f32 * CARRY_WEIGHT_PER_STRENGTH
+    }
+
+    /// Whether the item fits within the weight limit that the player's Strength allows now.
+    pub fn can_carry(&self, item: &Item) -> bool {
+        self.inventory.total_weight() + item.weight() <= self.weight_limit()
+    }
+
+    pub fn pickup(&mut self, item: Item) -> Result<(), InventoryError> {
+        if !self.can_carry(&item) {
+            return Err(InventoryError::new(item, InventoryErrorReason::TooHeavy));
+        }
+
+        self.inventory.add_stacking(item)
     }
 
-    pub fn unnequip(&mut self, item: &Item) -> Option<Item> {
-        if let Some(index) = self.equippement.iter().position(|x| x == item) {
-            return Some(self.equippement.items.remove(index));
+    pub fn max_life(&self) -> i32 {
+        self.get_derived_stats()
+            .get(StatType::Life)
+            .map(|x| x.value)
+            .unwrap_or(0)
+    }
+
+    /// Applies the use effect of a consumable in the inventory, spending one of its
+    /// charges or, for items without charges, one from the stack.
+    pub fn use_item(&mut self, id: Uuid) -> bool {
+        let Some(item) = self.inventory.items.iter_mut().find(|x| x.id == id) else {
+            return false;
+        };
+        let Some(effect) = item.use_effect() else {
+            return false;
+        };
+
+        match item.charges {
+            Some(0) => return false,
+            Some(charges) => item.charges = Some(charges - 1),
+            None => {
+                self.inventory.take(id, 1);
+            }
+        }
+
+        effect.apply(self);
+        true
+    }
+
+    pub fn level(&self) -> i32 {
+        self.base_stats.get(StatType::Level).map(|x| x.value).unwrap_or(1)
+    }
+
+    /// Adds experience and raises the level as far as the curve allows, granting attribute
+    /// points for each level gained. Returns one event per level gained.
+    pub fn gain_experience(&mut self, amount: u64, curve: &ExperienceCurve) -> Vec<LevelUp> {
+        self.experience = self.experience.saturating_add(amount);
+
+        let mut level_ups = Vec::new();
+        let target = curve.level_for(self.experience);
+        let growth = CharacterClass::find(&self.class).map(|x| x.growth.as_slice()).unwrap_or_default();
+        for level in self.level() + 1..=target {
+            for (stat_type, value) in growth.iter() {
+                match self.base_stats.get_mut(*stat_type) {
+                    Some(stat) => stat.value += value,
+                    None => self.base_stats.add(Stat::new(*stat_type, *value)),
+                }
+            }
+            self.attribute_points += ATTRIBUTE_POINTS_PER_LEVEL;
+            level_ups.push(LevelUp {
+                level,
+                attribute_points: ATTRIBUTE_POINTS_PER_LEVEL,
+            });
+        }
+
+        if let Some(last) = level_ups.last() {
+            match self.base_stats.get_mut(StatType::Level) {
+                Some(stat) => stat.value = last.level,
+                None => self.base_stats.add(Stat::new(StatType::Level, last.level)),
+            }
+        }
+        level_ups
+    }
+
+    /// Moves an unspent attribute point into Strength, Dexterity or Intelligence.
+    pub fn spend_attribute_point(&mut self, stat_type: StatType) -> bool {
+        if self.attribute_points == 0
+            || !matches!(stat_type, StatType::Strength | StatType::Dexterity | StatType::Intelligence)
+        {
+            return false;
+        }
+
+        match self.base_stats.get_mut(stat_type) {
+            Some(stat) => stat.value += 1,
+            None => self.base_stats.add(Stat::new(stat_type, 1)),
+        }
+        self.attribute_points -= 1;
+        true
+    }
+
+    /// Passive points earned so far that are not allocated.
+    pub fn passive_points(&self) -> u32 {
+        let earned = (self.level() - 1).max(0) as u32 * PASSIVE_POINTS_PER_LEVEL;
+        earned.saturating_sub(self.passives.len() as u32)
+    }
+
+    pub fn allocate_passive(&mut self, id: &str) -> bool {
+        if self.passive_points() == 0 || !PassiveTree::standard().can_allocate(&self.class, &self.passives, id) {
+            return false;
+        }
+        self.passives.push(id.to_string());
+        true
+    }
+
+    pub fn refund_passive(&mut self, id: &str) -> bool {
+        if !PassiveTree::standard().can_refund(&self.class, &self.passives, id) {
+            return false;
+        }
+        self.passives.retain(|x| x != id);
+        true
+    }
+
+    /// Applies one of a carried currency stack to an equipped or carried item, such as a
+    /// Scroll of Wisdom to identify it. Fails if the currency has no effect on the item.
+    pub fn apply_currency(&mut self, currency_id: Uuid, target_id: Uuid) -> bool {
+        let Some(effect) = self.inventory.iter().find(|x| x.id == currency_id).and_then(|x| x.currency_effect()) else {
+            return false;
+        };
+        let Some(target) = self
+            .equippement
+            .iter_mut()
+            .chain(self.inventory.items.iter_mut())
+            .find(|x| x.id == target_id)
+        else {
+            return false;
+        };
+        if target_id == currency_id || !effect.can_apply_to(target) {
+            return false;
+        }
+
+        effect.apply(target, &mut Rng::new());
+        self.inventory.take(currency_id, 1);
+        true
+    }
+
+    /// One carried stack of each kind of currency that would have an effect on the item.
+    pub fn currencies_for(&self, item: &Item) -> Vec<&Item> {
+        let mut currencies: Vec<&Item> = Vec::new();
+        for currency in self.inventory.iter() {
+            let applies = currency.currency_effect().is_some_and(|x| x.can_apply_to(item));
+            if applies && currency != item && !currencies.iter().any(|x| x.item_base == currency.item_base) {
+                currencies.push(currency);
+            }
         }
-        None
+        currencies
     }
 
-    pub fn pickup(&mut self, item: Item) {
-        self.inventory.add(item);
+    /// Wears down every equipped item that has durability, as happens in combat.
+    pub fn wear_equipment(&mut self, amount: u32) {
+        for item in self.equippement.iter_mut() {
+            item.wear(amount);
+        }
     }
 
-    pub fn drop(&mut self, item: &Item) -> Option<Item>{
-        if let Some(index) = self.inventory.iter().position(|x| x == item) {
-            return Some(self.inventory.items.remove(index));
+    /// Restores an equipped or carried item to full durability, paying the
+    /// [`vendor::repair_cost`]. Fails if the item is undamaged or the player cannot afford it.
+    pub fn repair(&mut self, id: Uuid) -> bool {
+        let Some(item) = self
+            .equippement
+            .iter_mut()
+            .chain(self.inventory.items.iter_mut())
+            .find(|x| x.id == id)
+        else {
+            return false;
+        };
+
+        let cost = vendor::repair_cost(item);
+        if cost == 0 || cost > self.gold {
+            return false;
         }
-        None
+        item.repair();
+        self.gold -= cost;
+        true
+    }
+
+    pub fn apply_buff(&mut self, buff: Buff) {
+        buff::apply(&mut self.effects, buff);
+    }
+
+    /// Advances timed effects by `elapsed`, keeping life within a maximum that may have
+    /// dropped as buffs ran out.
+    pub fn tick(&mut self, elapsed: Duration) {
+        buff::tick(&mut self.effects, elapsed);
+        self.life = self.life.min(self.max_life());
     }
 
     pub fn get_derived_stats(&self) -> StatBlock {
+        self.derive_stats(self.equippement.iter())
+    }
+
+    pub(super) fn derive_stats<'a>(&self, equipped: impl Iterator<Item = &'a Item>) -> StatBlock {
         let mut base_stats = self.base_stats.clone();
-        for item in self.equippement.iter() {
+        // Broken items do nothing until they are repaired.
+        for item in equipped.filter(|x| !x.is_broken()) {
+            // Defences add up across the gear; other item stats, like weapon damage, come
+            // from the first item that has them.
             for stat in item.get_derived_stats().stats.iter() {
-                if !self.base_stats.has(stat.stat_type) {
-                    base_stats.add(stat.clone())
+                if self.base_stats.has(stat.stat_type) {
+                    continue;
+                }
+                match base_stats.get_mut(stat.stat_type) {
+                    Some(total) if stat.stat_type.is_defence() => total.value += stat.value,
+                    Some(_) => {}
+                    None => base_stats.add(*stat),
                 }
             }
 
-            for modifier in item.modifiers.iter() {
+            for modifier in item.active_modifiers() {
+                modifier.apply_to(Character(&mut base_stats))
+            }
+        }
+
+        let tree = PassiveTree::standard();
+        for node in self.passives.iter().filter_map(|x| tree.node(x)) {
+            for modifier in node.modifiers.iter() {
                 modifier.apply_to(Character(&mut base_stats))
             }
         }
+
+        for effect in self.effects.iter() {
+            match base_stats.get_mut(effect.buff.stat) {
+                Some(stat) => stat.value += effect.value(),
+                None => base_stats.add(Stat::new(effect.buff.stat, effect.value())),
+            }
+        }
+
+        if let Some(block) = base_stats.get_mut(StatType::BlockChance) {
+            block.value = block.value.min(MAX_BLOCK_CHANCE);
+        }
         base_stats
     }
+
+    /// Compares the player's derived stats before and after `candidate` replaces
+    /// whatever is equipped in its slot. Returns `None` if the item cannot be equipped.
+    pub fn compare_equip(&self, candidate: &Item) -> Option<EquipmentComparison> {
+        let slot = self.target_slot(candidate)?;
+        if !self.can_use(candidate) {
+            return None;
+        }
+        let replaced = self.displaced_by(candidate, slot);
+
+        let mut after_slots: Vec<(EquipmentSlot, &Item)> = self
+            .equippement
+            .slots()
+            .filter(|(_, x)| !replaced.contains(x) && *x != candidate)
+            .collect();
+        after_slots.push((slot, candidate));
+        after_slots.sort_by_key(|x| x.0);
+        let after_items: Vec<&Item> = after_slots.into_iter().map(|x| x.1).collect();
+
+        let before = self.get_derived_stats();
+        let after = self.derive_stats(after_items.iter().copied());
+
+        let mut requirements = Vec::new();
+        for item in after_items {
+            for requirement in item.get_derived_requirements().requirements {
+                let met_before = requirement.is_met_by(&before);
+                let met_after = requirement.is_met_by(&after);
+                if met_before != met_after {
+                    requirements.push(RequirementChange {
+                        item_name: item.display_name().to_string(),
+                        requirement,
+                        is_met: met_after,
+                    });
+                }
+            }
+        }
+
+        Some(EquipmentComparison {
+            slot,
+            replaced: replaced.iter().map(|x| x.display_name().to_string()).collect(),
+            stats: EquipmentComparison::stat_deltas(&before, &after),
+            requirements,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item_builder::item;
+    use crate::model::modifier::{FlatStatModifier, ModifierTargetKind};
+
+    fn warrior() -> Player {
+        Player::new(CharacterClass::find("Warrior").unwrap())
+    }
+
+    #[test]
+    fn weight_limit_follows_current_strength() {
+        let mut player = warrior();
+        for _ in 0..4 {
+            player.pickup(item("Sledgehammer")).unwrap();
+        }
+
+        player.base_stats.get_mut(StatType::Strength).unwrap().value = 2;
+        let error = player.pickup(item("Iron Ring")).unwrap_err();
+        assert!(matches!(error.reason, InventoryErrorReason::TooHeavy));
+
+        player.attribute_points = 2;
+        for _ in 0..2 {
+            assert!(player.spend_attribute_point(StatType::Strength));
+        }
+        player.pickup(item("Iron Ring")).unwrap();
+    }
+
+    #[test]
+    fn carried_items_are_limited() {
+        let mut player = warrior();
+        while player.inventory.len() < MAX_CARRIED_ITEMS {
+            player.pickup(item("Iron Ring")).unwrap();
+        }
+
+        let error = player.pickup(item("Iron Ring")).unwrap_err();
+        assert!(matches!(error.reason, InventoryErrorReason::TooManyItems));
+    }
+
+    fn slots(player: &Player) -> Vec<(EquipmentSlot, &str)> {
+        player.equippement.slots().map(|(slot, x)| (slot, x.item_base.as_str())).collect()
+    }
+
+    #[test]
+    fn a_second_one_handed_weapon_goes_in_the_off_hand() {
+        let mut player = warrior();
+        assert!(player.equip(item("Club")).unwrap().is_empty());
+        assert_eq!(slots(&player), [(EquipmentSlot::Weapon, "Hand Axe"), (EquipmentSlot::OffHand, "Club")]);
+
+        let replaced = player.equip(item("Short Sword")).unwrap();
+        assert_eq!(replaced.iter().map(|x| x.item_base.as_str()).collect::<Vec<_>>(), ["Hand Axe"]);
+        assert_eq!(slots(&player), [(EquipmentSlot::Weapon, "Short Sword"), (EquipmentSlot::OffHand, "Club")]);
+    }
+
+    #[test]
+    fn two_handed_weapons_take_both_hands() {
+        let mut player = warrior();
+        player.equip(item("Buckler")).unwrap();
+
+        let replaced = player.equip(item("Claymore")).unwrap();
+        assert_eq!(replaced.len(), 2);
+        assert_eq!(slots(&player), [(EquipmentSlot::Weapon, "Claymore")]);
+
+        let replaced = player.equip(item("Buckler")).unwrap();
+        assert_eq!(replaced.iter().map(|x| x.item_base.as_str()).collect::<Vec<_>>(), ["Claymore"]);
+        assert_eq!(slots(&player), [(EquipmentSlot::OffHand, "Buckler")]);
+    }
+
+    #[test]
+    fn class_restricted_items_are_given_back() {
+        let mut player = Player::new(CharacterClass::find("Ranger").unwrap());
+        let claymore = player.equip(item("Claymore")).unwrap_err();
+        assert_eq!(claymore.item_base, "Claymore");
+        assert!(player.compare_equip(&claymore).is_none());
+        assert!(player.equip(item("Health Potion")).is_err());
+    }
+
+    #[test]
+    fn equipping_from_the_inventory_swaps_the_items() {
+        let mut player = warrior();
+        player.pickup(item("Claymore")).unwrap();
+        let id = player.inventory.iter().find(|x| x.item_base == "Claymore").unwrap().id;
+
+        assert!(player.equip_from_inventory(id));
+        assert_eq!(slots(&player), [(EquipmentSlot::Weapon, "Claymore")]);
+        assert!(player.inventory.iter().any(|x| x.item_base == "Hand Axe"));
+
+        assert!(player.unequip(EquipmentSlot::Weapon));
+        assert!(player.equippement.iter().next().is_none());
+        assert!(!player.unequip(EquipmentSlot::Weapon));
+    }
+
+    #[test]
+    fn nothing_changes_when_the_replaced_items_cannot_be_carried() {
+        let mut player = warrior();
+        player.equip(item("Tower Shield")).unwrap();
+        player.pickup(item("Claymore")).unwrap();
+        let claymore = player.inventory.iter().find(|x| x.item_base == "Claymore").unwrap();
+        let (id, position) = (claymore.id, player.inventory.position_of(claymore));
+        player.base_stats.get_mut(StatType::Strength).unwrap().value = -100;
+
+        assert!(!player.equip_from_inventory(id));
+        assert_eq!(slots(&player), [(EquipmentSlot::Weapon, "Hand Axe"), (EquipmentSlot::OffHand, "Tower Shield")]);
+        let claymore = player.inventory.iter().find(|x| x.id == id).unwrap();
+        assert_eq!(player.inventory.position_of(claymore), position);
+
+        assert!(!player.unequip(EquipmentSlot::OffHand));
+        assert_eq!(player.equipped_in(EquipmentSlot::OffHand).unwrap().item_base, "Tower Shield");
+    }
+
+    #[test]
+    fn comparing_a_two_handed_weapon_counts_both_hands() {
+        let mut player = warrior();
+        player.equip(item("Buckler")).unwrap();
+
+        let comparison = player.compare_equip(&item("Claymore")).unwrap();
+        assert_eq!(comparison.slot, EquipmentSlot::Weapon);
+        assert_eq!(comparison.replaced, ["Hand Axe", "Buckler"]);
+        let block = comparison.changed_stats().find(|x| x.stat_type == StatType::BlockChance).unwrap();
+        assert_eq!((block.before, block.after), (20, 0));
+    }
+
+    #[test]
+    fn items_the_class_cannot_use_are_not_compared() {
+        assert!(warrior().compare_equip(&item("Sage's Circlet")).is_none());
+    }
+
+    #[test]
+    fn levelling_up_grants_points_for_every_level() {
+        let mut player = warrior();
+        let strength = player.base_stats.get(StatType::Strength).unwrap().value;
+
+        let level_ups = player.gain_experience(400, &ExperienceCurve::default());
+        assert_eq!(level_ups.iter().map(|x| x.level).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(player.level(), 3);
+        assert_eq!(player.attribute_points, 2 * ATTRIBUTE_POINTS_PER_LEVEL);
+        assert!(player.base_stats.get(StatType::Strength).unwrap().value > strength);
+        assert!(player.gain_experience(1, &ExperienceCurve::default()).is_empty());
+    }
+
+    #[test]
+    fn attribute_points_only_go_into_attributes() {
+        let mut player = warrior();
+        assert!(!player.spend_attribute_point(StatType::Strength));
+
+        player.attribute_points = 1;
+        assert!(!player.spend_attribute_point(StatType::Life));
+        assert!(player.spend_attribute_point(StatType::Intelligence));
+        assert_eq!(player.attribute_points, 0);
+    }
+
+    #[test]
+    fn passives_need_points_earned_by_levelling() {
+        let mut player = warrior();
+        assert!(!player.allocate_passive("might"));
+
+        player.gain_experience(ExperienceCurve::default().required_for(2), &ExperienceCurve::default());
+        assert!(player.allocate_passive("might"));
+        assert!(!player.allocate_passive("toughness"));
+        let strength = |stats: &StatBlock| stats.get(StatType::Strength).unwrap().value;
+        assert_eq!(strength(&player.get_derived_stats()), strength(&player.base_stats) + 10);
+
+        assert!(player.refund_passive("might"));
+        assert_eq!(player.passive_points(), PASSIVE_POINTS_PER_LEVEL);
+    }
+
+    #[test]
+    fn broken_gear_does_nothing_until_repaired() {
+        let mut player = warrior();
+        let axe = player.equipped_in(EquipmentSlot::Weapon).unwrap().id;
+        let damage = |player: &Player| player.get_derived_stats().get(StatType::MaximumDamage).map(|x| x.value);
+        let sharp = damage(&player);
+
+        player.wear_equipment(u32::MAX);
+        assert!(player.equipped_in(EquipmentSlot::Weapon).unwrap().is_broken());
+        assert_eq!(damage(&player), None);
+
+        assert!(!player.repair(axe));
+        player.gold = vendor::repair_cost(player.equipped_in(EquipmentSlot::Weapon).unwrap());
+        assert!(player.repair(axe));
+        assert_eq!(player.gold, 0);
+        assert_eq!(damage(&player), sharp);
+        assert!(!player.repair(axe));
+    }
+
+    #[test]
+    fn defences_add_up_and_block_is_capped() {
+        let mut player = warrior();
+        let shield = Item::builder()
+            .base(String::from("Tower Shield"))
+            .with_modifier(FlatStatModifier { value: 60, stat: StatType::BlockChance, target: ModifierTargetKind::Item })
+            .build()
+            .unwrap();
+        player.equip(shield).unwrap();
+        player.equip(item("Plate Vest")).unwrap();
+
+        let stats = player.get_derived_stats();
+        assert_eq!(stats.get(StatType::Armour).map(|x| x.value), Some(55));
+        assert_eq!(stats.get(StatType::BlockChance).map(|x| x.value), Some(MAX_BLOCK_CHANCE));
+    }
 }
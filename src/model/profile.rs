@@ -0,0 +1,14 @@
+use crate::model::stash::Stash;
+
+/// What the characters of one profile share: for now, the stash. Each character is
+/// saved on its own, while the profile is saved apart from all of them.
+pub struct Profile {
+    pub name: String,
+    pub stash: Stash,
+}
+
+impl Profile {
+    pub fn new(name: String, stash: Stash) -> Self {
+        Self { name, stash }
+    }
+}
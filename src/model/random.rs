@@ -0,0 +1,63 @@
+use std::ops::RangeInclusive;
+use uuid::Uuid;
+
+/// A small xorshift generator for rolling loot. Not suitable for anything that needs to be
+/// unpredictable.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Seeds from a random UUID, which is the one source of entropy the crate already has.
+    pub fn new() -> Self {
+        let bits = Uuid::new_v4().as_u128();
+        Self::seeded((bits >> 64) as u64 ^ bits as u64)
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        // Zero is the one state xorshift never leaves.
+        Self { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i32>) -> i32 {
+        let (start, end) = (*range.start() as i64, *range.end() as i64);
+        if end <= start {
+            return start as i32;
+        }
+        (start + (self.next_u64() % (end - start + 1) as u64) as i64) as i32
+    }
+
+    /// Picks an entry with probability proportional to its weight.
+    pub fn weighted<'a, T>(&mut self, entries: &'a [T], weight: impl Fn(&T) -> u32) -> Option<&'a T> {
+        let total: u64 = entries.iter().map(|x| weight(x) as u64).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = self.next_u64() % total;
+        for entry in entries {
+            let weight = weight(entry) as u64;
+            if roll < weight {
+                return Some(entry);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::model::stat::{Stat, StatType};
+use crate::model::stat::{Stat, StatBlock, StatType};
 
 #[derive(Debug, Clone)]
 pub struct StatRequirement {
@@ -10,6 +10,13 @@ impl StatRequirement {
     pub fn is_met(&self, stat: &Stat) -> bool {
         self.stat_type == stat.stat_type && self.amount <= stat.value
     }
+
+    pub fn is_met_by(&self, stats: &StatBlock) -> bool {
+        stats
+            .get(self.stat_type)
+            .map(|s| self.is_met(s))
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -17,6 +24,12 @@ pub struct RequirementBlock {
     pub requirements: Vec<StatRequirement>
 }
 
+impl RequirementBlock {
+    pub fn is_met_by(&self, stats: &StatBlock) -> bool {
+        self.requirements.iter().all(|r| r.is_met_by(stats))
+    }
+}
+
 impl Default for RequirementBlock {
     fn default() -> Self {
         RequirementBlock {
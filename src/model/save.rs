@@ -0,0 +1,603 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::model::equipment::{Equipment, EquipmentSlot};
+use crate::model::inventory::{GridPosition, Inventory};
+use crate::model::item::Item;
+use crate::model::item_base::ItemBase;
+use crate::model::modifier;
+use crate::model::player::{MAX_CARRIED_ITEMS, Player};
+use crate::model::profile::Profile;
+use crate::model::requirement::{RequirementBlock, StatRequirement};
+use crate::model::stash::{Stash, StashTab};
+use crate::model::stat::{Stat, StatBlock};
+
+/// Upgrades the entries of a save written in one schema version to the next.
+type Migration = fn(&mut Vec<SaveEntry>) -> Result<(), SaveError>;
+
+/// `CHARACTER_MIGRATIONS[n]` upgrades a version `n + 1` character save to version `n + 2`.
+/// When the schema changes, append a migration here rather than changing how older saves
+/// are read.
+const CHARACTER_MIGRATIONS: &[Migration] = &[];
+
+/// Upgrades profile saves, which are versioned apart from characters in the same way.
+const PROFILE_MIGRATIONS: &[Migration] = &[];
+
+pub const SAVE_VERSION: u32 = CHARACTER_MIGRATIONS.len() as u32 + 1;
+pub const PROFILE_VERSION: u32 = PROFILE_MIGRATIONS.len() as u32 + 1;
+
+#[derive(Debug)]
+pub struct SaveError(String);
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "Save error: {}", self.0)
+    }
+}
+
+/// A line of a save file: a key, the rest of the line as its value, and the entries of
+/// the `{ ... }` block that follows it, if any. Values are escaped when written, so a
+/// name holding braces or line breaks cannot end a line or a block early.
+#[derive(Debug, Clone)]
+struct SaveEntry {
+    key: String,
+    value: String,
+    children: Vec<SaveEntry>,
+}
+
+impl SaveEntry {
+    fn new(key: &str, value: impl Display) -> Self {
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+            children: vec![],
+        }
+    }
+
+    fn block(key: &str, children: Vec<SaveEntry>) -> Self {
+        Self {
+            key: key.to_string(),
+            value: String::new(),
+            children,
+        }
+    }
+
+    fn write(&self, out: &mut String, depth: usize) {
+        out.push_str(&"    ".repeat(depth));
+        out.push_str(&self.key);
+        if !self.value.is_empty() {
+            out.push(' ');
+            out.push_str(&escape(&self.value));
+        }
+        if !self.children.is_empty() {
+            out.push_str(" {\n");
+            for child in self.children.iter() {
+                child.write(out, depth + 1);
+            }
+            out.push_str(&"    ".repeat(depth));
+            out.push('}');
+        }
+        out.push('\n');
+    }
+
+    fn get(&self, key: &str) -> Option<&SaveEntry> {
+        self.children.iter().find(|x| x.key == key)
+    }
+
+    fn all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a SaveEntry> {
+        self.children.iter().filter(move |x| x.key == key)
+    }
+
+    fn require(&self, key: &str) -> Result<&SaveEntry, SaveError> {
+        self.get(key)
+            .ok_or(SaveError(format!("'{}' is missing '{}'", self.key, key)))
+    }
+}
+
+pub fn save_player(player: &Player, path: impl AsRef<Path>) -> Result<(), SaveError> {
+    write_save(path.as_ref(), &encode_player(player))
+}
+
+pub fn load_player(path: impl AsRef<Path>) -> Result<Player, SaveError> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| SaveError(format!("could not read {}: {}", path.display(), e)))?;
+    decode_player(&contents)
+}
+
+pub fn encode_player(player: &Player) -> String {
+    let mut entries = vec![SaveEntry::new("version", SAVE_VERSION)];
+    entries.extend(encode_stats(&player.base_stats));
+    entries.push(SaveEntry::new("life", player.life));
+    entries.push(SaveEntry::new("experience", player.experience));
+    entries.push(SaveEntry::new("attribute_points", player.attribute_points));
+    entries.push(SaveEntry::new("class", &player.class));
+    entries.push(SaveEntry::new("gold", player.gold));
+    for passive in player.passives.iter() {
+        entries.push(SaveEntry::new("passive", passive));
+    }
+    entries.push(SaveEntry::block("inventory", encode_inventory(&player.inventory)));
+    entries.push(SaveEntry::block("equipment", encode_equipment(&player.equippement)));
+
+    let mut out = String::new();
+    for entry in entries.iter() {
+        entry.write(&mut out, 0);
+    }
+    out
+}
+
+/// Reads a save of any known version, migrating it to the current schema first.
+pub fn decode_player(s: &str) -> Result<Player, SaveError> {
+    let root = parse_save(s, SAVE_VERSION, CHARACTER_MIGRATIONS)?;
+    let mut player = Player {
+        base_stats: decode_stats(&root)?,
+        inventory: decode_inventory(root.require("inventory")?)?,
+        equippement: decode_equipment(root.require("equipment")?)?,
+        life: parse_number(&root.require("life")?.value)?,
+        effects: Vec::new(),
+        experience: parse_number(&root.require("experience")?.value)?,
+        attribute_points: parse_number(&root.require("attribute_points")?.value)?,
+        class: root.require("class")?.value.clone(),
+        passives: root.all("passive").map(|x| x.value.clone()).collect(),
+        gold: parse_number(&root.require("gold")?.value)?,
+    };
+    player.life = player.life.min(player.max_life());
+    // Limits are game rules rather than saved state. They are set after the items are
+    // loaded, so a save is never rejected for a load it already carried.
+    player.inventory.max_slots = Some(MAX_CARRIED_ITEMS);
+    Ok(player)
+}
+
+pub fn save_profile(profile: &Profile, path: impl AsRef<Path>) -> Result<(), SaveError> {
+    write_save(path.as_ref(), &encode_profile(profile))
+}
+
+pub fn load_profile(path: impl AsRef<Path>) -> Result<Profile, SaveError> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| SaveError(format!("could not read {}: {}", path.display(), e)))?;
+    decode_profile(&contents)
+}
+
+pub fn encode_profile(profile: &Profile) -> String {
+    let mut entries = vec![
+        SaveEntry::new("version", PROFILE_VERSION),
+        SaveEntry::new("name", &profile.name),
+    ];
+    for tab in profile.stash.tabs() {
+        let mut tab_entry = SaveEntry::block("tab", encode_inventory(&tab.items));
+        tab_entry.value = tab.name.clone();
+        entries.push(tab_entry);
+    }
+
+    let mut out = String::new();
+    for entry in entries.iter() {
+        entry.write(&mut out, 0);
+    }
+    out
+}
+
+/// Reads a profile of any known version, migrating it to the current schema first.
+pub fn decode_profile(s: &str) -> Result<Profile, SaveError> {
+    let root = parse_save(s, PROFILE_VERSION, PROFILE_MIGRATIONS)?;
+    let tabs = root
+        .all("tab")
+        .map(|x| {
+            Ok(StashTab {
+                name: x.value.clone(),
+                items: decode_inventory(x)?,
+            })
+        })
+        .collect::<Result<Vec<_>, SaveError>>()?;
+    Ok(Profile::new(root.require("name")?.value.clone(), Stash::from_tabs(tabs)))
+}
+
+/// Writes the whole save to a file beside `path` and then moves it over `path`, so a save
+/// interrupted part way leaves the previous one intact.
+fn write_save(path: &Path, contents: &str) -> Result<(), SaveError> {
+    let write_error = |e: std::io::Error| SaveError(format!("could not write {}: {}", path.display(), e));
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| SaveError(format!("could not create {}: {}", dir.display(), e)))?;
+    }
+
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let mut file = File::create(&temp).map_err(write_error)?;
+    file.write_all(contents.as_bytes()).map_err(write_error)?;
+    file.sync_all().map_err(write_error)?;
+    std::fs::rename(&temp, path).map_err(write_error)
+}
+
+/// Parses a save and runs the `migrations` it has not been through, returning its root
+/// entry.
+fn parse_save(s: &str, current: u32, migrations: &[Migration]) -> Result<SaveEntry, SaveError> {
+    let mut entries = parse_entries(&mut s.lines().enumerate(), false)?;
+
+    let version = match entries.first() {
+        Some(entry) if entry.key == "version" => parse_number::<u32>(&entry.value)?,
+        _ => return Err(SaveError(String::from("missing version"))),
+    };
+    if version == 0 || version > current {
+        return Err(SaveError(format!("unsupported version {}", version)));
+    }
+
+    for migration in migrations.iter().skip(version as usize - 1) {
+        migration(&mut entries)?;
+    }
+    Ok(SaveEntry::block("save", entries))
+}
+
+fn parse_entries<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    in_block: bool,
+) -> Result<Vec<SaveEntry>, SaveError> {
+    let mut entries = Vec::new();
+
+    while let Some((index, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "}" {
+            if !in_block {
+                return Err(SaveError(format!("line {}: unexpected '}}'", index + 1)));
+            }
+            return Ok(entries);
+        }
+
+        let (line, opens_block) = match line.strip_suffix(" {") {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let mut entry = SaveEntry::new(key, unescape(value.trim()));
+        if opens_block {
+            entry.children = parse_entries(lines, true)?;
+        }
+        entries.push(entry);
+    }
+
+    if in_block {
+        return Err(SaveError(String::from("unterminated block")));
+    }
+    Ok(entries)
+}
+
+/// Backslash escapes the characters that structure a save, and spaces at either end of a
+/// value, which reading would otherwise trim.
+fn escape(value: &str) -> String {
+    let last = value.chars().count().saturating_sub(1);
+    let mut out = String::with_capacity(value.len());
+    for (index, c) in value.chars().enumerate() {
+        match c {
+            '\\' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            ' ' if index == 0 || index == last => out.push_str("\\s"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('s') => out.push(' '),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, SaveError> {
+    s.parse().map_err(|_| SaveError(format!("'{}' is not a number", s)))
+}
+
+fn parse_pair<T: std::str::FromStr>(s: &str) -> Result<(&str, T), SaveError> {
+    let (name, value) = s
+        .rsplit_once(' ')
+        .ok_or(SaveError(format!("expected a name and a value in '{}'", s)))?;
+    Ok((name, parse_number(value)?))
+}
+
+fn encode_stats(stats: &StatBlock) -> Vec<SaveEntry> {
+    stats
+        .stats
+        .iter()
+        .map(|x| SaveEntry::new("stat", format!("{:?} {}", x.stat_type, x.value)))
+        .collect()
+}
+
+fn decode_stats(entry: &SaveEntry) -> Result<StatBlock, SaveError> {
+    let mut stats = StatBlock::default();
+    for stat in entry.all("stat") {
+        let (name, value) = parse_pair(&stat.value)?;
+        stats.add(Stat::new(name.parse().map_err(SaveError)?, value));
+    }
+    Ok(stats)
+}
+
+fn encode_inventory(inventory: &Inventory) -> Vec<SaveEntry> {
+    let mut entries = Vec::new();
+    if let Some(grid) = inventory.grid() {
+        entries.push(SaveEntry::new("grid", format!("{} {}", grid.width, grid.height)));
+    }
+    for item in inventory.iter() {
+        let mut children = encode_item(item);
+        if let Some(position) = inventory.position_of(item) {
+            children.push(SaveEntry::new("position", format!("{} {}", position.x, position.y)));
+        }
+        entries.push(SaveEntry::block("item", children));
+    }
+    entries
+}
+
+fn decode_inventory(entry: &SaveEntry) -> Result<Inventory, SaveError> {
+    let mut inventory = match entry.get("grid") {
+        Some(grid) => {
+            let (width, height) = parse_pair(&grid.value)?;
+            Inventory::with_grid(parse_number(width)?, height)
+        }
+        None => Inventory::new(),
+    };
+    for item_entry in entry.all("item") {
+        let item = decode_item(item_entry)?;
+        let added = match item_entry.get("position") {
+            Some(position) => {
+                let (x, y) = parse_pair(&position.value)?;
+                inventory.place(item, GridPosition { x: parse_number(x)?, y })
+            }
+            None => inventory.add(item),
+        };
+        added.map_err(|e| SaveError(e.to_string()))?;
+    }
+    Ok(inventory)
+}
+
+fn encode_equipment(equipment: &Equipment) -> Vec<SaveEntry> {
+    equipment
+        .slots()
+        .map(|(slot, item)| {
+            let mut children = encode_item(item);
+            children.push(SaveEntry::new("slot", format!("{:?}", slot)));
+            SaveEntry::block("item", children)
+        })
+        .collect()
+}
+
+fn decode_equipment(entry: &SaveEntry) -> Result<Equipment, SaveError> {
+    let mut equipment = Equipment::new();
+    for item_entry in entry.all("item") {
+        let item = decode_item(item_entry)?;
+        let slot: EquipmentSlot = item_entry.require("slot")?.value.parse().map_err(SaveError)?;
+        if !slot.accepts(&item.item_class) {
+            return Err(SaveError(format!("{} cannot go in the {} slot", item.item_base, slot)));
+        }
+        if equipment.insert(slot, item).is_some() {
+            return Err(SaveError(format!("more than one item in the {} slot", slot)));
+        }
+    }
+    Ok(equipment)
+}
+
+fn encode_item(item: &Item) -> Vec<SaveEntry> {
+    let mut entries = vec![
+        SaveEntry::new("id", item.id),
+        SaveEntry::new("base", &item.item_base),
+    ];
+    if let Some(name) = &item.name {
+        entries.push(SaveEntry::new("name", name));
+    }
+    entries.push(SaveEntry::new("rarity", format!("{:?}", item.rarity)));
+    entries.push(SaveEntry::new("class", &item.item_class));
+    entries.push(SaveEntry::new("level", item.item_level));
+    entries.push(SaveEntry::new("quantity", item.quantity));
+    if let Some(charges) = item.charges {
+        entries.push(SaveEntry::new("charges", charges));
+    }
+    if let Some(durability) = item.durability {
+        entries.push(SaveEntry::new("durability", durability));
+    }
+    if !item.identified {
+        entries.push(SaveEntry::new("identified", false));
+    }
+    if item.quality > 0 {
+        entries.push(SaveEntry::new("quality", item.quality));
+    }
+    entries.extend(encode_stats(&item.base_stats));
+    for requirement in item.requirements.requirements.iter() {
+        entries.push(SaveEntry::new(
+            "requirement",
+            format!("{:?} {}", requirement.stat_type, requirement.amount),
+        ));
+    }
+    for implicit in item.implicits.iter() {
+        entries.push(SaveEntry::new("implicit", implicit.encode()));
+    }
+    for modifier in item.modifiers.iter() {
+        entries.push(SaveEntry::new("modifier", modifier.encode()));
+    }
+    entries
+}
+
+fn decode_item(entry: &SaveEntry) -> Result<Item, SaveError> {
+    let value = |key: &str| entry.require(key).map(|x| x.value.as_str());
+
+    let mut requirements = RequirementBlock::default();
+    for requirement in entry.all("requirement") {
+        let (name, amount) = parse_pair(&requirement.value)?;
+        requirements.requirements.push(StatRequirement {
+            stat_type: name.parse().map_err(SaveError)?,
+            amount,
+        });
+    }
+
+    let quantity: u32 = parse_number(value("quantity")?)?;
+    let max_stack = ItemBase::find(value("base")?).map(|x| x.max_stack).unwrap_or(1);
+    if quantity == 0 || quantity > max_stack {
+        return Err(SaveError(format!("quantity {} is outside the stack size of {} ({})", quantity, value("base")?, max_stack)));
+    }
+
+    // Items saved before durability existed come back fully repaired.
+    let base_durability = ItemBase::find(value("base")?).and_then(|x| x.max_durability);
+
+    Ok(Item {
+        id: Uuid::parse_str(value("id")?).map_err(|e| SaveError(e.to_string()))?,
+        item_base: value("base")?.to_string(),
+        name: entry.get("name").map(|x| x.value.clone()),
+        rarity: value("rarity")?.parse().map_err(SaveError)?,
+        item_class: value("class")?.parse().map_err(SaveError)?,
+        item_level: parse_number(value("level")?)?,
+        quantity,
+        charges: entry.get("charges").map(|x| parse_number(&x.value)).transpose()?,
+        durability: entry
+            .get("durability")
+            .map(|x| parse_number(&x.value))
+            .transpose()?
+            .or(base_durability),
+        identified: entry
+            .get("identified")
+            .map(|x| x.value.parse().map_err(|_| SaveError(format!("'{}' is not true or false", x.value))))
+            .transpose()?
+            .unwrap_or(true),
+        quality: entry.get("quality").map(|x| parse_number(&x.value)).transpose()?.unwrap_or(0),
+        requirements,
+        base_stats: decode_stats(entry)?,
+        implicits: entry
+            .all("implicit")
+            .map(|x| modifier::decode(&x.value).map_err(SaveError))
+            .collect::<Result<Vec<_>, _>>()?,
+        modifiers: entry
+            .all("modifier")
+            .map(|x| modifier::decode(&x.value).map_err(SaveError))
+            .collect::<Result<Vec<_>, _>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::model::character_class::CharacterClass;
+    use crate::model::item_builder::item;
+    use crate::model::modifier::{FlatStatModifier, ModifierTargetKind};
+    use crate::model::stat::StatType;
+
+    /// Writes the item out as save text and reads it back.
+    fn round_trip(item: &Item) -> Item {
+        let mut out = String::new();
+        SaveEntry::block("item", encode_item(item)).write(&mut out, 0);
+        let entries = parse_entries(&mut out.lines().enumerate(), false).unwrap();
+        decode_item(&entries[0]).unwrap()
+    }
+
+    #[test]
+    fn every_stat_type_survives_a_round_trip() {
+        for stat_type in StatType::iter() {
+            let item = Item::builder()
+                .base(String::from("Short Sword"))
+                .with_stat(stat_type, 7)
+                .with_requirement(stat_type, 3)
+                .with_modifier(FlatStatModifier { value: 5, stat: stat_type, target: ModifierTargetKind::Item })
+                .build()
+                .unwrap();
+
+            let loaded = round_trip(&item);
+            assert_eq!(loaded.base_stats.get(stat_type).map(|x| x.value), Some(7), "{:?}", stat_type);
+            assert_eq!(loaded.requirements.requirements[0].stat_type, stat_type);
+            assert_eq!(loaded.modifiers[0].get_affected_stat(), stat_type);
+        }
+    }
+
+    #[test]
+    fn quantities_beyond_the_stack_size_are_rejected() {
+        let item = Item::builder()
+            .base(String::from("Health Potion"))
+            .quantity(10)
+            .build()
+            .unwrap();
+        let mut entries = encode_item(&item);
+        entries.iter_mut().find(|x| x.key == "quantity").unwrap().value = String::from("11");
+
+        assert!(decode_item(&SaveEntry::block("item", entries)).is_err());
+    }
+
+    #[test]
+    fn profile_stash_survives_a_round_trip() {
+        let ring = item("Iron Ring");
+        let id = ring.id;
+        let mut gear = StashTab::new(String::from("Gear and Such"));
+        gear.items.place(ring, GridPosition { x: 4, y: 5 }).unwrap();
+        let stash = Stash::from_tabs(vec![StashTab::new(String::from("Currency")), gear]);
+
+        let loaded = decode_profile(&encode_profile(&Profile::new(String::from("Default"), stash))).unwrap();
+        assert_eq!(loaded.name, "Default");
+        let names: Vec<&str> = loaded.stash.tabs().iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, ["Currency", "Gear and Such"]);
+        let items = &loaded.stash.tab(1).unwrap().items;
+        let ring = items.iter().find(|x| x.id == id).unwrap();
+        assert_eq!(items.position_of(ring), Some(GridPosition { x: 4, y: 5 }));
+    }
+
+    fn dual_wielding_warrior() -> Player {
+        let mut player = Player::new(CharacterClass::find("Warrior").unwrap());
+        player.equip(Item::builder().base(String::from("Club")).build().unwrap()).unwrap();
+        player
+    }
+
+    fn slots(player: &Player) -> Vec<(EquipmentSlot, &str)> {
+        player.equippement.slots().map(|(slot, item)| (slot, item.item_base.as_str())).collect()
+    }
+
+    #[test]
+    fn equipment_slots_survive_a_round_trip() {
+        let loaded = decode_player(&encode_player(&dual_wielding_warrior())).unwrap();
+        assert_eq!(slots(&loaded), [(EquipmentSlot::Weapon, "Hand Axe"), (EquipmentSlot::OffHand, "Club")]);
+    }
+
+    #[test]
+    fn names_that_look_like_save_syntax_survive_a_round_trip() {
+        let names = ["}", "Loot {", "Two\nLines", " Padded\\ ", "version 1"];
+        let tabs = names.iter().map(|x| StashTab::new(x.to_string())).collect();
+        let profile = Profile::new(String::from("{ Default }"), Stash::from_tabs(tabs));
+
+        let loaded = decode_profile(&encode_profile(&profile)).unwrap();
+        assert_eq!(loaded.name, "{ Default }");
+        let loaded_names: Vec<&str> = loaded.stash.tabs().iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(loaded_names, names);
+    }
+
+    #[test]
+    fn saves_from_a_newer_version_are_rejected() {
+        let save = encode_player(&dual_wielding_warrior()).replacen(
+            &format!("version {}", SAVE_VERSION),
+            &format!("version {}", SAVE_VERSION + 1),
+            1,
+        );
+        assert!(decode_player(&save).is_err());
+    }
+
+    #[test]
+    fn items_in_the_wrong_slot_are_rejected() {
+        let save = encode_player(&dual_wielding_warrior()).replace("slot OffHand", "slot Helmet");
+        assert!(decode_player(&save).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use uuid::Uuid;
+
+use crate::model::inventory::{GridPosition, Inventory};
+use crate::model::item::Item;
+use crate::model::player::Player;
+
+const STASH_TAB_WIDTH: usize = 12;
+const STASH_TAB_HEIGHT: usize = 12;
+
+pub struct StashTab {
+    pub name: String,
+    pub items: Inventory,
+}
+
+impl StashTab {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            items: Inventory::with_grid(STASH_TAB_WIDTH, STASH_TAB_HEIGHT),
+        }
+    }
+}
+
+/// Storage kept apart from any one character, so it can be shared by every
+/// character of a [`Profile`](crate::model::profile::Profile).
+pub struct Stash {
+    tabs: Vec<StashTab>,
+}
+
+impl Stash {
+    pub fn from_tabs(tabs: Vec<StashTab>) -> Self {
+        Self { tabs }
+    }
+
+    pub fn with_tabs(names: &[&str]) -> Self {
+        Self {
+            tabs: names.iter().map(|x| StashTab::new(x.to_string())).collect(),
+        }
+    }
+
+    pub fn add_tab(&mut self, name: String) -> usize {
+        self.tabs.push(StashTab::new(name));
+        self.tabs.len() - 1
+    }
+
+    /// Removes a tab, but only once it has been emptied.
+    pub fn remove_tab(&mut self, tab: usize) -> Option<StashTab> {
+        if !self.tabs.get(tab)?.items.is_empty() {
+            return None;
+        }
+        Some(self.tabs.remove(tab))
+    }
+
+    pub fn tab(&self, tab: usize) -> Option<&StashTab> {
+        self.tabs.get(tab)
+    }
+
+    pub fn tabs(&self) -> &[StashTab] {
+        &self.tabs
+    }
+
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    /// Moves an item from the player's inventory into a tab, stacking it where possible.
+    pub fn deposit(&mut self, player: &mut Player, tab: usize, id: Uuid) -> bool {
+        let Some(stash_tab) = self.tabs.get_mut(tab) else {
+            return false;
+        };
+        transfer(&mut player.inventory, &mut stash_tab.items, id)
+    }
+
+    /// Moves an item from a tab into the player's inventory, subject to the player's
+    /// weight and room. The item stays in the tab if it cannot be picked up.
+    pub fn withdraw(&mut self, player: &mut Player, tab: usize, id: Uuid) -> bool {
+        let Some(stash_tab) = self.tabs.get_mut(tab) else {
+            return false;
+        };
+        let Some((item, position)) = take_whole(&mut stash_tab.items, id) else {
+            return false;
+        };
+
+        match player.pickup(item) {
+            Ok(()) => true,
+            Err(e) => {
+                restore(&mut stash_tab.items, *e.item, position);
+                false
+            }
+        }
+    }
+
+    pub fn move_item(&mut self, from: usize, to: usize, id: Uuid) -> bool {
+        if from == to || from >= self.tabs.len() || to >= self.tabs.len() {
+            return false;
+        }
+
+        let (low, high) = self.tabs.split_at_mut(from.max(to));
+        let (source, target) = if from < to {
+            (&mut low[from], &mut high[0])
+        } else {
+            (&mut high[0], &mut low[to])
+        };
+        transfer(&mut source.items, &mut target.items, id)
+    }
+}
+
+pub(super) fn take_whole(inventory: &mut Inventory, id: Uuid) -> Option<(Item, Option<GridPosition>)> {
+    let item = inventory.iter().find(|x| x.id == id)?;
+    let (position, quantity) = (inventory.position_of(item), item.quantity);
+    Some((inventory.take(id, quantity)?, position))
+}
+
+/// Puts an item back where it was just taken from. Its cells and its slot are still
+/// free, as nothing else could change the inventory in the meantime, so this cannot fail.
+pub(super) fn restore(inventory: &mut Inventory, item: Item, position: Option<GridPosition>) {
+    let restored = match position {
+        Some(position) => inventory.place(item, position),
+        None => inventory.add(item),
+    };
+    restored.expect("an item fits back where it was just taken from");
+}
+
+/// Moves a whole item between inventories, putting back whatever does not fit.
+fn transfer(from: &mut Inventory, to: &mut Inventory, id: Uuid) -> bool {
+    let Some((item, position)) = take_whole(from, id) else {
+        return false;
+    };
+
+    match to.add_stacking(item) {
+        Ok(()) => true,
+        Err(e) => {
+            restore(from, *e.item, position);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::character_class::CharacterClass;
+    use crate::model::item_builder::item;
+    use crate::model::stat::StatType;
+
+    #[test]
+    fn a_failed_withdraw_leaves_the_item_in_place() {
+        let mut player = Player::new(CharacterClass::find("Warrior").unwrap());
+        let mut stash = Stash::with_tabs(&["Gear"]);
+        stash.tabs[0].items.place(item("Iron Ring"), GridPosition { x: 3, y: 2 }).unwrap();
+        let id = stash.tabs[0].items.iter().next().unwrap().id;
+
+        player.base_stats.get_mut(StatType::Strength).unwrap().value = -100;
+        assert!(!stash.withdraw(&mut player, 0, id));
+
+        let items = &stash.tabs[0].items;
+        let ring = items.iter().find(|x| x.id == id).unwrap();
+        assert_eq!(items.position_of(ring), Some(GridPosition { x: 3, y: 2 }));
+    }
+
+    #[test]
+    fn items_move_between_tabs_and_only_empty_tabs_are_removed() {
+        let mut stash = Stash::with_tabs(&["Gear", "Dump"]);
+        stash.tabs[0].items.add(item("Iron Ring")).unwrap();
+        let id = stash.tabs[0].items.iter().next().unwrap().id;
+
+        assert!(stash.remove_tab(0).is_none());
+        assert!(stash.move_item(0, 1, id));
+        assert!(stash.tabs[1].items.iter().any(|x| x.id == id));
+        assert!(stash.remove_tab(0).is_some());
+        assert_eq!(stash.tabs()[0].name, "Dump");
+    }
+}
@@ -1,4 +1,6 @@
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use strum::{EnumIter, IntoEnumIterator};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Stat {
@@ -12,7 +14,7 @@ impl Stat {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter)]
 pub enum StatType {
     Strength,
     Intelligence,
@@ -23,10 +25,53 @@ pub enum StatType {
     IncreasedAttackSpeed,
     MinimumDamage,
     MaximumDamage,
-    Defense,
+    /// A weapon's attacks per second, in hundredths.
+    AttackSpeed,
+    /// A weapon's chance to deal a critical strike, as a percentage.
+    CriticalChance,
+    /// How far a weapon reaches.
+    Range,
+    Armour,
+    Evasion,
+    EnergyShield,
+    /// Raises every kind of defence on the item it is on.
+    IncreasedDefences,
+    BlockChance,
     Life,
 }
 
+impl StatType {
+    /// Stats that add up across all equipped items rather than coming from a single one.
+    pub const DEFENCES: [StatType; 4] =
+        [StatType::Armour, StatType::Evasion, StatType::EnergyShield, StatType::BlockChance];
+
+    pub fn is_defence(&self) -> bool {
+        StatType::DEFENCES.contains(self)
+    }
+
+    /// Writes a value of this stat the way players read it, such as `1.50` attacks per
+    /// second or `20%` block chance.
+    pub fn format_value(&self, value: i32) -> String {
+        match self {
+            StatType::AttackSpeed => format!("{:.2}", value as f32 / 100.0),
+            StatType::CriticalChance | StatType::BlockChance => format!("{}%", value),
+            _ => value.to_string(),
+        }
+    }
+
+    /// Reads a value written by [`StatType::format_value`].
+    pub fn parse_value(&self, s: &str) -> Result<i32, String> {
+        let invalid = || format!("'{}' is not a value for {}", s, self);
+        match self {
+            StatType::AttackSpeed => s.parse::<f32>().map(|x| (x * 100.0).round() as i32).map_err(|_| invalid()),
+            StatType::CriticalChance | StatType::BlockChance => {
+                s.strip_suffix('%').unwrap_or(s).parse().map_err(|_| invalid())
+            }
+            _ => s.parse().map_err(|_| invalid()),
+        }
+    }
+}
+
 impl Display for StatType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -38,26 +83,31 @@ impl Display for StatType {
             StatType::IncreasedDamage => write!(f, "Increased Damage"),
             StatType::MinimumDamage => write!(f, "Minimum Damage"),
             StatType::MaximumDamage => write!(f, "Maximum Damage"),
-            StatType::Defense => write!(f, "Defence"),
+            StatType::AttackSpeed => write!(f, "Attacks per Second"),
+            StatType::CriticalChance => write!(f, "Critical Chance"),
+            StatType::Range => write!(f, "Range"),
+            StatType::Armour => write!(f, "Armour"),
+            StatType::Evasion => write!(f, "Evasion"),
+            StatType::EnergyShield => write!(f, "Energy Shield"),
+            StatType::IncreasedDefences => write!(f, "Increased Defences"),
+            StatType::BlockChance => write!(f, "Block Chance"),
             StatType::IncreasedAttackSpeed => write!(f, "Increased Attack Speed"),
             StatType::Life => write!(f, "Life"),
         }
     }
 }
 
-impl PartialEq for StatType {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (StatType::Strength, StatType::Strength) => true,
-            (StatType::Dexterity, StatType::Dexterity) => true,
-            (StatType::Intelligence, StatType::Intelligence) => true,
-            (StatType::Level, StatType::Level) => true,
-            (StatType::MinimumDamage, StatType::MinimumDamage) => true,
-            (StatType::MaximumDamage, StatType::MaximumDamage) => true,
-            (StatType::IncreasedDamage, StatType::IncreasedDamage) => true,
-            (StatType::Requirements, StatType::Requirements) => true,
-            _ => false,
-        }
+impl FromStr for StatType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        StatType::iter()
+            .find(|x| {
+                // Saves write stats by their variant name, which can differ from the display name.
+                x.to_string().replace(' ', "").eq_ignore_ascii_case(&name) || format!("{:?}", x).eq_ignore_ascii_case(&name)
+            })
+            .ok_or(format!("Unknown stat: {}", s))
     }
 }
 
@@ -92,3 +142,20 @@ impl Default for StatBlock {
         Self { stats: vec![] }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_stat_can_be_looked_up() {
+        let mut stats = StatBlock::default();
+        stats.add(Stat::new(StatType::Life, 40));
+        stats.add(Stat::new(StatType::Armour, 12));
+        stats.add(Stat::new(StatType::Life, 10));
+
+        assert_eq!(stats.get(StatType::Life).map(|x| x.value), Some(40));
+        assert_eq!(stats.get(StatType::Armour).map(|x| x.value), Some(12));
+        assert!(!stats.has(StatType::IncreasedAttackSpeed));
+    }
+}
@@ -0,0 +1,56 @@
+use crate::model::item::{EquipmentType, ItemClass};
+
+/// Splits a line into words, keeping double-quoted text, quotes included, as one word.
+pub(super) fn tokenize(s: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in s.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(String::from("unterminated quote"));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+/// Whether `text` equals, or with `contains` holds, the lowercase `pattern`, ignoring case.
+pub(super) fn matches_text(text: &str, pattern: &str, contains: bool) -> bool {
+    let text = text.to_lowercase();
+    if contains {
+        text.contains(pattern)
+    } else {
+        text == pattern
+    }
+}
+
+/// Whether the class is named by the lowercase `pattern`, which may also be one of the
+/// categories `equipment`, `weapon`, `armour` or `jewellery`.
+pub(super) fn matches_class(item_class: &ItemClass, pattern: &str, contains: bool) -> bool {
+    let category = match item_class {
+        ItemClass::Equipment(EquipmentType::Weapon(_)) => "weapon",
+        ItemClass::Equipment(EquipmentType::Armour(_)) => "armour",
+        ItemClass::Equipment(EquipmentType::Jewellery(_)) => "jewellery",
+        _ => "",
+    };
+
+    (pattern == "equipment" && matches!(item_class, ItemClass::Equipment(_)))
+        || category == pattern
+        || matches_text(&item_class.to_string(), pattern, contains)
+}
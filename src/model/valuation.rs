@@ -0,0 +1,234 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::model::affix::Affix;
+use crate::model::item::Item;
+use crate::model::text_match::{matches_class, tokenize};
+use crate::model::modifier::Modifier;
+use crate::model::stat::StatType;
+
+const STANDARD_WEIGHTS: &str = include_str!("../../data/valuation.weights");
+
+/// Points a perfect roll of a modifier with weight 1 is worth.
+const MODIFIER_POINTS: f32 = 100.0;
+
+static STANDARD_VALUATION: LazyLock<Valuation> =
+    LazyLock::new(|| STANDARD_WEIGHTS.parse().expect("the standard valuation weights are valid"));
+
+#[derive(Debug)]
+pub struct ValuationError(String);
+
+impl Display for ValuationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "Valuation error: {}", self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ClassWeights {
+    classes: Vec<String>,
+    weights: Vec<(StatType, f32)>,
+}
+
+impl ClassWeights {
+    fn matches(&self, item: &Item) -> bool {
+        self.classes.iter().any(|x| matches_class(&item.item_class, x, false))
+    }
+
+    fn weight(&self, stat_type: StatType) -> f32 {
+        self.weights.iter().find(|x| x.0 == stat_type).map(|x| x.1).unwrap_or(0.0)
+    }
+}
+
+/// What one modifier adds to an item's estimated value.
+#[derive(Debug, Clone)]
+pub struct ModifierValue {
+    pub description: String,
+    /// How far through its affix's range the modifier rolled, from just above 0 for the
+    /// lowest roll to 1 for the highest, or `None` if its range is unknown. Modifiers with an
+    /// unknown range score nothing, as how good their roll is cannot be told.
+    pub quality: Option<f32>,
+    pub weight: f32,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Appraisal {
+    pub score: f32,
+    pub modifiers: Vec<ModifierValue>,
+}
+
+impl Display for Appraisal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Score {:.0}", self.score)?;
+        for modifier in self.modifiers.iter() {
+            let quality = match modifier.quality {
+                Some(quality) => format!("{:.0}% roll", quality * 100.0),
+                None => String::from("unknown range, not scored"),
+            };
+            write!(
+                f,
+                "\n  {:.0}: {} ({}, weight {})",
+                modifier.score, modifier.description, quality, modifier.weight
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Estimates how valuable items probably are from their modifiers: each modifier scores for
+/// how well it rolled within its affix's range, weighted by how much the item's class wants
+/// the stat it affects. Modifiers that match no affix are left out of the score.
+#[derive(Debug, Clone)]
+pub struct Valuation {
+    blocks: Vec<ClassWeights>,
+}
+
+impl Valuation {
+    /// The weights in `data/valuation.weights`.
+    pub fn standard() -> &'static Valuation {
+        &STANDARD_VALUATION
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Valuation, ValuationError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ValuationError(format!("could not read {}: {}", path.display(), e)))?;
+        contents.parse()
+    }
+
+    pub fn appraise(&self, item: &Item) -> Appraisal {
+        let block = self.blocks.iter().find(|x| x.matches(item));
+
+        let modifiers: Vec<ModifierValue> = item
+            .known_modifiers()
+            .iter()
+            .map(|modifier| {
+                let quality = roll_quality(modifier.as_ref());
+                let weight = block.map(|x| x.weight(modifier.get_affected_stat())).unwrap_or(0.0);
+                ModifierValue {
+                    description: modifier.description(),
+                    quality,
+                    weight,
+                    score: quality.map(|x| weight * x * MODIFIER_POINTS).unwrap_or(0.0),
+                }
+            })
+            .collect();
+
+        Appraisal {
+            score: modifiers.iter().map(|x| x.score).sum(),
+            modifiers,
+        }
+    }
+}
+
+fn roll_quality(modifier: &dyn Modifier) -> Option<f32> {
+    let affix = Affix::find_for(modifier)?;
+    let value = *modifier.values().first()?;
+    let (min, max) = (*affix.values.start(), *affix.values.end());
+    let quality = (value - min + 1) as f32 / (max - min + 1) as f32;
+    Some(quality.clamp(0.0, 1.0))
+}
+
+impl FromStr for Valuation {
+    type Err = ValuationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut blocks: Vec<ClassWeights> = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let at_line = |e: ValuationError| ValuationError(format!("line {}: {}", index + 1, e.0));
+            let tokens: Vec<String> = tokenize(line)
+                .map_err(ValuationError)
+                .map_err(at_line)?
+                .into_iter()
+                .map(|x| x.trim_matches('"').to_string())
+                .collect();
+            let (keyword, args) = tokens.split_first().expect("line is not empty");
+
+            match (keyword.as_str(), args) {
+                ("Class", classes) if !classes.is_empty() => blocks.push(ClassWeights {
+                    classes: classes.iter().map(|x| x.to_lowercase()).collect(),
+                    weights: Vec::new(),
+                }),
+                ("Weight", [stat, weight]) => {
+                    let Some(block) = blocks.last_mut() else {
+                        return Err(at_line(ValuationError(String::from("'Weight' outside of a Class block"))));
+                    };
+                    let stat_type = stat.parse().map_err(|e| at_line(ValuationError(e)))?;
+                    let weight = weight
+                        .parse()
+                        .map_err(|_| at_line(ValuationError(format!("'{}' is not a number", weight))))?;
+                    block.weights.push((stat_type, weight));
+                }
+                _ => return Err(at_line(ValuationError(format!("unexpected '{}'", line)))),
+            }
+        }
+
+        Ok(Valuation { blocks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::affix::AffixKind;
+    use crate::model::item::ItemRarity;
+    use crate::model::modifier::ModifierTargetKind;
+
+    fn sword(affixes: &[(AffixKind, i32)]) -> Item {
+        let mut item = Item::builder()
+            .base(String::from("Short Sword"))
+            .rarity(ItemRarity::Rare)
+            .build()
+            .unwrap();
+        item.modifiers = affixes.iter().map(|(kind, value)| kind.build(*value)).collect();
+        item
+    }
+
+    #[test]
+    fn better_rolls_score_higher() {
+        let valuation: Valuation = "Class Weapon\nWeight \"Increased Damage\" 1".parse().unwrap();
+        let low = valuation.appraise(&sword(&[(AffixKind::IncreasedDamage(ModifierTargetKind::Item), 20)]));
+        let high = valuation.appraise(&sword(&[(AffixKind::IncreasedDamage(ModifierTargetKind::Item), 120)]));
+        assert!(high.score > low.score);
+        assert_eq!(high.score, MODIFIER_POINTS);
+    }
+
+    #[test]
+    fn modifiers_with_an_unknown_range_are_not_scored() {
+        let valuation: Valuation = "Class Weapon\nWeight \"Increased Damage\" 1".parse().unwrap();
+        let unknown = valuation.appraise(&sword(&[(AffixKind::IncreasedDamage(ModifierTargetKind::Character), 10)]));
+        assert_eq!(unknown.score, 0.0);
+        assert_eq!(unknown.modifiers[0].quality, None);
+        assert!(unknown.to_string().contains("not scored"));
+
+        let known = valuation.appraise(&sword(&[(AffixKind::IncreasedDamage(ModifierTargetKind::Item), 80)]));
+        assert!(known.score > unknown.score);
+    }
+
+    #[test]
+    fn the_standard_weights_parse() {
+        assert!(!Valuation::standard().blocks.is_empty());
+    }
+
+    #[test]
+    fn malformed_weights_are_rejected() {
+        for text in [
+            "Weight Strength 1",
+            "Class Weapon\nWeight Luck 1",
+            "Class Weapon\nWeight Strength lots",
+            "Class",
+            "Class Weapon\nWeight Strength",
+        ] {
+            assert!(text.parse::<Valuation>().is_err(), "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::model::inventory::Inventory;
+use crate::model::item::{Item, ItemRarity};
+use crate::model::item_base::ItemBase;
+use crate::model::loot_table::LootTable;
+use crate::model::player::Player;
+use crate::model::random::Rng;
+use crate::model::stash::{restore, take_whole};
+
+/// Vendors sell items for this many times what they pay for them.
+pub const BUY_MARKUP: u32 = 4;
+const MODIFIER_VALUE: u32 = 3;
+const REPAIR_COST_PER_POINT: u32 = 1;
+const RESTOCK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// What a vendor pays for one of the item, before the stack size.
+fn unit_price(item: &Item) -> u32 {
+    let value = ItemBase::find(&item.item_base).map(|x| x.value).unwrap_or(0);
+    value * rarity_multiplier(item.rarity) + item.known_modifiers().len() as u32 * MODIFIER_VALUE
+}
+
+fn rarity_multiplier(rarity: ItemRarity) -> u32 {
+    match rarity {
+        ItemRarity::Normal => 1,
+        ItemRarity::Magic => 2,
+        ItemRarity::Rare => 4,
+        ItemRarity::Unique => 10,
+    }
+}
+
+/// Gold the player gets for selling the item: its base's value, scaled by rarity,
+/// plus a little for each modifier, for every item in the stack.
+pub fn sell_price(item: &Item) -> u32 {
+    unit_price(item) * item.quantity
+}
+
+pub fn buy_price(item: &Item) -> u32 {
+    sell_price(item) * BUY_MARKUP
+}
+
+/// Gold to restore the item to full durability: a little for each missing point, scaled
+/// by rarity. Free for items that are undamaged or have no durability.
+pub fn repair_cost(item: &Item) -> u32 {
+    let missing = match (item.durability, item.max_durability()) {
+        (Some(durability), Some(max_durability)) => max_durability.saturating_sub(durability),
+        _ => 0,
+    };
+    missing * REPAIR_COST_PER_POINT * rarity_multiplier(item.rarity)
+}
+
+pub struct Vendor {
+    pub name: String,
+    pub stock: Inventory,
+    loot_tables: Vec<&'static LootTable>,
+    item_level: u32,
+    stock_size: usize,
+    rng: Rng,
+    /// Time left until the next restock.
+    pub restock_in: Duration,
+}
+
+impl Vendor {
+    pub fn new(name: impl Into<String>, loot_tables: Vec<&'static LootTable>, item_level: u32) -> Self {
+        let mut vendor = Self {
+            name: name.into(),
+            stock: Inventory::with_grid(12, 8),
+            loot_tables,
+            item_level,
+            stock_size: 12,
+            rng: Rng::new(),
+            restock_in: RESTOCK_INTERVAL,
+        };
+        vendor.restock();
+        vendor
+    }
+
+    /// Replaces the stock, including anything the player sold, with freshly rolled items
+    /// from the vendor's loot tables.
+    pub fn restock(&mut self) {
+        let grid = self.stock.grid().map(|x| (x.width, x.height)).unwrap_or((12, 8));
+        self.stock = Inventory::with_grid(grid.0, grid.1);
+        self.restock_in = RESTOCK_INTERVAL;
+        if self.loot_tables.is_empty() {
+            return;
+        }
+
+        for index in 0..self.stock_size {
+            let table = self.loot_tables[index % self.loot_tables.len()];
+            if let Some(mut item) = table.roll(&mut self.rng, self.item_level) {
+                // Vendors know what they are selling.
+                item.identify();
+                // A full grid just means a smaller stock this time.
+                let _ = self.stock.add(item);
+            }
+        }
+    }
+
+    /// Counts down to the next restock, restocking when it is due. Returns whether it did.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        self.restock_in = self.restock_in.saturating_sub(elapsed);
+        if !self.restock_in.is_zero() {
+            return false;
+        }
+        self.restock();
+        true
+    }
+
+    /// Moves an item from the player's inventory into the stock and pays the player.
+    pub fn sell(&mut self, player: &mut Player, id: Uuid) -> bool {
+        let Some((item, position)) = take_whole(&mut player.inventory, id) else {
+            return false;
+        };
+
+        let price = sell_price(&item);
+        match self.stock.add(item) {
+            Ok(()) => {
+                player.gold = player.gold.saturating_add(price);
+                true
+            }
+            Err(e) => {
+                restore(&mut player.inventory, *e.item, position);
+                false
+            }
+        }
+    }
+
+    /// Sells an item from the stock to the player, if they can afford and carry it.
+    pub fn buy(&mut self, player: &mut Player, id: Uuid) -> bool {
+        let Some(price) = self.stock.iter().find(|x| x.id == id).map(buy_price) else {
+            return false;
+        };
+        if player.gold < price {
+            return false;
+        }
+        let Some((item, position)) = take_whole(&mut self.stock, id) else {
+            return false;
+        };
+
+        match player.pickup(item) {
+            Ok(()) => {
+                player.gold -= price;
+                true
+            }
+            Err(e) => {
+                restore(&mut self.stock, *e.item, position);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::character_class::CharacterClass;
+    use crate::model::item_builder::item;
+
+    #[test]
+    fn sold_items_can_be_bought_back_at_a_markup() {
+        let mut player = Player::new(CharacterClass::find("Warrior").unwrap());
+        let mut vendor = Vendor::new("Trader", Vec::new(), 1);
+        let ring = item("Iron Ring");
+        let id = ring.id;
+        let price = sell_price(&ring);
+        player.pickup(ring).unwrap();
+
+        assert!(vendor.sell(&mut player, id));
+        assert_eq!(player.gold, price);
+        assert!(player.inventory.iter().all(|x| x.id != id));
+
+        assert!(!vendor.buy(&mut player, id));
+        player.gold = price * BUY_MARKUP;
+        assert!(vendor.buy(&mut player, id));
+        assert_eq!(player.gold, 0);
+        assert!(vendor.stock.is_empty());
+    }
+
+    #[test]
+    fn only_carried_items_can_be_sold() {
+        let mut player = Player::new(CharacterClass::find("Warrior").unwrap());
+        let mut vendor = Vendor::new("Trader", Vec::new(), 1);
+        let axe = player.equippement.iter().next().unwrap().id;
+
+        assert!(!vendor.sell(&mut player, axe));
+        assert_eq!(player.gold, 0);
+    }
+
+    #[test]
+    fn restocking_replaces_sold_items() {
+        let mut player = Player::new(CharacterClass::find("Warrior").unwrap());
+        let mut vendor = Vendor::new("Trader", Vec::new(), 1);
+        let ring = item("Iron Ring");
+        let id = ring.id;
+        player.pickup(ring).unwrap();
+        vendor.sell(&mut player, id);
+
+        assert!(!vendor.tick(RESTOCK_INTERVAL - Duration::from_secs(1)));
+        assert_eq!(vendor.stock.len(), 1);
+        assert!(vendor.tick(Duration::from_secs(1)));
+        assert!(vendor.stock.is_empty());
+        assert_eq!(vendor.restock_in, RESTOCK_INTERVAL);
+    }
+
+    #[test]
+    fn prices_come_from_the_base() {
+        assert_eq!(sell_price(&item("Claymore")), ItemBase::find("Claymore").unwrap().value);
+        assert!(sell_price(&item("Claymore")) > sell_price(&item("Short Sword")));
+    }
+
+    #[test]
+    fn selling_never_overflows_gold() {
+        let mut player = Player::new(CharacterClass::find("Warrior").unwrap());
+        let mut vendor = Vendor::new("Trader", Vec::new(), 1);
+        let ring = item("Iron Ring");
+        let id = ring.id;
+        player.pickup(ring).unwrap();
+        player.gold = u32::MAX - 1;
+
+        assert!(vendor.sell(&mut player, id));
+        assert_eq!(player.gold, u32::MAX);
+    }
+
+    #[test]
+    fn rarity_raises_prices_and_repairs() {
+        let mut magic = item("Iron Ring");
+        magic.rarity = ItemRarity::Magic;
+        assert_eq!(sell_price(&magic), 2 * sell_price(&item("Iron Ring")));
+
+        let mut axe = item("Hand Axe");
+        assert_eq!(repair_cost(&axe), 0);
+        axe.durability = axe.durability.map(|x| x - 5);
+        assert_eq!(repair_cost(&axe), 5 * REPAIR_COST_PER_POINT);
+    }
+}
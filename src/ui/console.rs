@@ -0,0 +1,279 @@
+use crate::model::character_class::CharacterClass;
+use crate::model::currency::CurrencyEffect;
+use crate::model::experience::ExperienceCurve;
+use crate::model::integrity;
+use crate::model::item::Item;
+use crate::model::item_query::ItemQuery;
+use crate::model::item_sort::SortKey;
+use crate::model::optimizer::{self, OptimizationTarget};
+use crate::model::player::Player;
+use crate::model::save;
+use crate::model::stash::Stash;
+use crate::model::stat::StatType;
+use crate::model::valuation::{Appraisal, Valuation};
+use crate::model::vendor;
+use uuid::Uuid;
+
+pub fn run_command(mut player: Player, stash: &Stash, args: &[String], save_path: &str) -> Result<(), String> {
+    match args.first().map(|x| x.as_str()) {
+        Some("export") => export(&player),
+        Some("import") => match args.get(1) {
+            Some(path) => import(player, path, save_path),
+            None => Err(String::from("No file to import given")),
+        },
+        Some("inventory") => inventory(&mut player, args.get(1).map(|x| x.as_str())),
+        Some("search") => search(&player, &args[1..].join(" ")),
+        Some("verify") => verify(&player, stash),
+        Some("optimize") => optimize(&player, args.get(1).map(|x| x.as_str()).unwrap_or("damage")),
+        Some("experience") => match args.get(1) {
+            Some(amount) => gain_experience(player, amount, save_path),
+            None => Err(String::from("No experience amount given")),
+        },
+        Some("spend") => match args.get(1) {
+            Some(stat) => spend(player, stat, args.get(2).map(|x| x.as_str()), save_path),
+            None => Err(String::from("No attribute given")),
+        },
+        Some("wear") => match args.get(1) {
+            Some(amount) => wear(player, amount, save_path),
+            None => Err(String::from("No wear amount given")),
+        },
+        Some("repair") => repair(player, save_path),
+        Some("identify") => identify(player, save_path),
+        Some("appraise") => appraise(&player, args.get(1).map(|x| x.as_str())),
+        Some("new") => match args.get(1) {
+            Some(class) => new_character(class, save_path),
+            None => Err(String::from("No class given")),
+        },
+        Some(command) => Err(format!("Unknown command: {}", command)),
+        None => Err(String::from("No command given")),
+    }
+}
+
+fn optimize(player: &Player, target: &str) -> Result<(), String> {
+    let target = match target.to_lowercase().as_str() {
+        "damage" | "dps" => OptimizationTarget::Damage,
+        stat => OptimizationTarget::Stat(stat.parse::<StatType>()?),
+    };
+
+    let gear_set = optimizer::optimize(player, target);
+
+    println!("Best gear set (score {:.2}):", gear_set.score);
+    for (slot, item) in gear_set.items.iter() {
+        println!("  {}: {}", slot, item.display_name());
+    }
+
+    Ok(())
+}
+
+fn inventory(player: &mut Player, sort: Option<&str>) -> Result<(), String> {
+    if let Some(sort) = sort {
+        let sort_key = match sort.to_lowercase().as_str() {
+            "rarity" => SortKey::Rarity,
+            "class" => SortKey::Class,
+            "name" => SortKey::Name,
+            "level" | "ilvl" => SortKey::ItemLevel,
+            "value" => SortKey::Value,
+            stat => SortKey::Stat(stat.parse::<StatType>()?),
+        };
+        player.inventory.sort_by(sort_key);
+    }
+
+    for (item_class, items) in player.inventory.group_by_class() {
+        println!("{}:", item_class);
+        for item in items {
+            if item.quantity > 1 {
+                println!("  {} x{}", item.display_name(), item.quantity);
+            } else {
+                println!("  {}", item.display_name());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn search(player: &Player, query: &str) -> Result<(), String> {
+    let query = query.parse::<ItemQuery>().map_err(|e| e.to_string())?;
+
+    for item in player.inventory.filter(|x| query.matches(x)) {
+        match item.display_name() {
+            name if name != item.item_base => println!("{} ({})", name, item.item_base),
+            name => println!("{}", name),
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints every identified item. Unidentified ones are skipped, with a note on stderr.
+fn export(player: &Player) -> Result<(), String> {
+    let mut items = Vec::new();
+    for item in player.equippement.iter().chain(player.inventory.iter()) {
+        match item.to_text() {
+            Ok(text) => items.push(text),
+            Err(e) => eprintln!("Skipped {}: {}", item.display_name(), e),
+        }
+    }
+    println!("{}", items.join("\n\n"));
+    Ok(())
+}
+
+/// Picks up every item in the file, items separated by blank lines, and saves the character.
+fn import(mut player: Player, path: &str, save_path: &str) -> Result<(), String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+
+    for block in contents.split("\n\n").filter(|x| !x.trim().is_empty()) {
+        let item = Item::from_text(block).map_err(|e| e.to_string())?;
+        let name = item.display_name().to_string();
+        player.pickup(item).map_err(|e| e.to_string())?;
+        println!("Picked up {}", name);
+    }
+
+    save::save_player(&player, save_path).map_err(|e| e.to_string())
+}
+
+fn gain_experience(mut player: Player, amount: &str, save_path: &str) -> Result<(), String> {
+    let amount = amount.parse::<u64>().map_err(|_| format!("'{}' is not a number", amount))?;
+
+    for level_up in player.gain_experience(amount, &ExperienceCurve::default()) {
+        println!("Reached level {} (+{} attribute points)", level_up.level, level_up.attribute_points);
+    }
+    println!("Experience: {}, unspent attribute points: {}", player.experience, player.attribute_points);
+
+    save::save_player(&player, save_path).map_err(|e| e.to_string())
+}
+
+fn spend(mut player: Player, stat: &str, count: Option<&str>, save_path: &str) -> Result<(), String> {
+    let stat_type = stat.parse::<StatType>()?;
+    let count = match count {
+        Some(count) => count.parse::<u32>().map_err(|_| format!("'{}' is not a number", count))?,
+        None => 1,
+    };
+
+    for _ in 0..count {
+        if !player.spend_attribute_point(stat_type) {
+            return Err(format!("Cannot spend a point into {}", stat_type));
+        }
+    }
+    println!("{} unspent attribute points left", player.attribute_points);
+
+    save::save_player(&player, save_path).map_err(|e| e.to_string())
+}
+
+fn wear(mut player: Player, amount: &str, save_path: &str) -> Result<(), String> {
+    let amount = amount.parse::<u32>().map_err(|_| format!("'{}' is not a number", amount))?;
+
+    player.wear_equipment(amount);
+    for item in player.equippement.iter().filter(|x| x.is_broken()) {
+        println!("{} is broken", item.display_name());
+    }
+
+    save::save_player(&player, save_path).map_err(|e| e.to_string())
+}
+
+/// Repairs every damaged item, equipped ones first, for as long as the gold lasts.
+fn repair(mut player: Player, save_path: &str) -> Result<(), String> {
+    let damaged: Vec<(Uuid, String, u32)> = player
+        .equippement
+        .iter()
+        .chain(player.inventory.iter())
+        .map(|x| (x.id, x.display_name().to_string(), vendor::repair_cost(x)))
+        .filter(|x| x.2 > 0)
+        .collect();
+
+    for (id, name, cost) in damaged {
+        if player.repair(id) {
+            println!("Repaired {} for {} gold", name, cost);
+        } else {
+            println!("Cannot afford to repair {} ({} gold)", name, cost);
+        }
+    }
+    println!("{} gold left", player.gold);
+
+    save::save_player(&player, save_path).map_err(|e| e.to_string())
+}
+
+/// Identifies every unidentified item with the carried currency, for as long as it lasts.
+fn identify(mut player: Player, save_path: &str) -> Result<(), String> {
+    let unidentified: Vec<Uuid> = player
+        .equippement
+        .iter()
+        .chain(player.inventory.iter())
+        .filter(|x| !x.identified)
+        .map(|x| x.id)
+        .collect();
+
+    for id in unidentified {
+        let Some(item) = player.equippement.iter().chain(player.inventory.iter()).find(|x| x.id == id) else {
+            continue;
+        };
+        let scroll = player
+            .currencies_for(item)
+            .into_iter()
+            .find(|x| x.currency_effect() == Some(CurrencyEffect::Identify));
+        let Some(currency) = scroll.map(|x| x.id) else {
+            return Err(String::from("Nothing left to identify with"));
+        };
+        player.apply_currency(currency, id);
+
+        let item = player.equippement.iter().chain(player.inventory.iter()).find(|x| x.id == id);
+        if let Some(item) = item {
+            println!("{} ({}):", item.display_name(), item.item_base);
+            for modifier in item.modifiers.iter() {
+                println!("  {}", modifier.description());
+            }
+        }
+    }
+
+    save::save_player(&player, save_path).map_err(|e| e.to_string())
+}
+
+/// Replaces the saved character with a new one of the given class.
+fn new_character(class: &str, save_path: &str) -> Result<(), String> {
+    let class = CharacterClass::all()
+        .iter()
+        .find(|x| x.name.eq_ignore_ascii_case(class))
+        .ok_or(format!("Unknown class: {}", class))?;
+
+    let player = Player::new(class);
+    println!("Created a level {} {}", player.level(), class.name);
+    save::save_player(&player, save_path).map_err(|e| e.to_string())
+}
+
+/// Lists every carried and equipped item with modifiers from most to least valuable,
+/// explaining each score. Weights come from the given file, or the standard ones.
+fn appraise(player: &Player, weights: Option<&str>) -> Result<(), String> {
+    let loaded;
+    let valuation = match weights {
+        Some(path) => {
+            loaded = Valuation::load(path).map_err(|e| e.to_string())?;
+            &loaded
+        }
+        None => Valuation::standard(),
+    };
+
+    let mut appraisals: Vec<(&Item, Appraisal)> = player
+        .inventory
+        .iter()
+        .chain(player.equippement.iter())
+        .filter(|x| !x.known_modifiers().is_empty())
+        .map(|x| (x, valuation.appraise(x)))
+        .collect();
+    appraisals.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+
+    for (item, appraisal) in appraisals {
+        println!("{} ({}): {}", item.display_name(), item.item_base, appraisal);
+    }
+    Ok(())
+}
+
+fn verify(player: &Player, stash: &Stash) -> Result<(), String> {
+    let violations = integrity::check_player(player, stash);
+    if violations.is_empty() {
+        println!("No problems found");
+    }
+    for violation in violations.iter() {
+        println!("{}", violation);
+    }
+    Ok(())
+}
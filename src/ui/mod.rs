@@ -1,2 +1,3 @@
 pub mod ratatui;
-mod focusable;
\ No newline at end of file
+mod focusable;
+pub mod console;
\ No newline at end of file
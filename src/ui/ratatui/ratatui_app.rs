@@ -1,29 +1,45 @@
 use crate::input::input_handler::{InputEvent, InputHandler};
+use crate::model::loot_filter::LootFilter;
+use crate::model::player::Player;
+use crate::model::stash::Stash;
+use crate::model::vendor::Vendor;
 use crate::ui::focusable::Focusable;
+use crate::ui::ratatui::state::passive::PassiveTreeState;
 use crate::ui::ratatui::state::player::PlayerState;
 use crate::ui::ratatui::state::popup::ItemPopupState;
+use crate::ui::ratatui::state::stash::StashState;
+use crate::ui::ratatui::state::vendor::VendorState;
 use crate::ui::ratatui::state::world::WorldState;
 use crate::ui::ratatui::view_models::item::ItemViewModel;
 use crate::ui::ratatui::widgets::equipment::PlayerEquipmentWidget;
 use crate::ui::ratatui::widgets::inventory::PlayerInventoryWidget;
+use crate::ui::ratatui::widgets::passive_tree::PassiveTreeWidget;
 use crate::ui::ratatui::widgets::player_stats::PlayerStatsWidget;
 use crate::ui::ratatui::widgets::item_popup::ItemPopupWidget;
+use crate::ui::ratatui::widgets::stash::StashWidget;
+use crate::ui::ratatui::widgets::status::StatusWidget;
+use crate::ui::ratatui::widgets::vendor::VendorWidget;
 use crate::ui::ratatui::widgets::world::WorldWidget;
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
 use ratatui::prelude::Direction;
-use ratatui::text::{Line};
-use ratatui::widgets::{Block, Clear};
+use ratatui::widgets::Clear;
 use ratatui::{DefaultTerminal, Frame};
 use std::cmp::PartialEq;
+use std::time::{Duration, Instant};
 use strum::{Display, EnumIter, IntoEnumIterator};
 
+const TICK_RATE: Duration = Duration::from_millis(250);
+
 #[derive(PartialEq, EnumIter, Display)]
 enum Screen {
     Stats,
     World,
     Equipment,
     Inventory,
+    Stash,
+    Passives,
+    Vendor,
 }
 
 pub enum PopupType {
@@ -34,30 +50,50 @@ pub struct RatatuiApp {
     exit: bool,
     player_state: PlayerState,
     world_state: WorldState,
+    stash_state: StashState,
+    passive_state: PassiveTreeState,
+    vendor_state: VendorState,
+    loot_filter: LootFilter,
     focus: Screen,
     popup: Option<PopupType>,
 }
 
 impl RatatuiApp {
-    pub fn new(player_state: PlayerState) -> Self {
+    pub fn new(player_state: PlayerState, stash: Stash, vendor: Vendor, loot_filter: LootFilter) -> Self {
         Self {
             exit: false,
             player_state,
             world_state: WorldState::new(),
+            stash_state: StashState::new(stash),
+            passive_state: PassiveTreeState::new(),
+            vendor_state: VendorState::new(vendor),
+            loot_filter,
             focus: Screen::Stats,
             popup: None,
         }
     }
+    pub fn player(&self) -> &Player {
+        &self.player_state.player
+    }
+
+    pub fn into_stash(self) -> Stash {
+        self.stash_state.stash
+    }
+
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
         self.change_screen(Screen::Stats);
+        let mut last_tick = Instant::now();
         while !self.should_exit() {
             terminal.draw(|frame| self.render(frame))?;
-            match crossterm::event::read()? {
-                crossterm::event::Event::Key(key) => {
-                    self.handle_key_event(key);
-                }
-                _ => {}
+            // Timed effects keep counting down while no key is pressed.
+            if crossterm::event::poll(TICK_RATE)?
+                && let crossterm::event::Event::Key(key) = crossterm::event::read()?
+            {
+                self.handle_key_event(key);
             }
+            self.player_state.tick(last_tick.elapsed());
+            self.vendor_state.tick(last_tick.elapsed());
+            last_tick = Instant::now();
         }
         Ok(())
     }
@@ -75,7 +111,7 @@ impl RatatuiApp {
         let main_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Length(25),
+                Constraint::Length(28),
                 Constraint::Min(0),
                 Constraint::Length(30),
             ])
@@ -90,14 +126,32 @@ impl RatatuiApp {
 
         let world = WorldWidget::new(&self.world_state);
 
-        let player_equipment = PlayerEquipmentWidget::new(&self.player_state.equipment_state);
+        let stash = StashWidget::new(&self.stash_state, &self.loot_filter);
+
+        let passive_tree = PassiveTreeWidget::new(&self.passive_state, &self.player_state.player);
+
+        let vendor = VendorWidget::new(&self.vendor_state, &self.loot_filter, self.player_state.player.gold);
 
-        let player_inventory = PlayerInventoryWidget::new(&self.player_state.inventory_state);
+        let player_equipment = PlayerEquipmentWidget::new(
+            &self.player_state.player,
+            &self.player_state.equipment_state,
+        );
 
-        let footer = Block::bordered().title(Line::from("Status").centered());
+        let player_inventory = PlayerInventoryWidget::new(
+            &self.player_state.player.inventory,
+            &self.player_state.inventory_state,
+            &self.loot_filter,
+        );
+
+        let footer = StatusWidget::new(&self.player_state.player);
 
         frame.render_widget(player_stats, main_layout[0]);
-        frame.render_widget(world, main_layout[1]);
+        match self.focus {
+            Screen::Stash => frame.render_widget(stash, main_layout[1]),
+            Screen::Passives => frame.render_widget(passive_tree, main_layout[1]),
+            Screen::Vendor => frame.render_widget(vendor, main_layout[1]),
+            _ => frame.render_widget(world, main_layout[1]),
+        }
         frame.render_widget(player_equipment, inventory_equipment_layout[0]);
         frame.render_widget(player_inventory, inventory_equipment_layout[1]);
         frame.render_widget(footer, root_layout[1]);
@@ -109,7 +163,7 @@ impl RatatuiApp {
                 PopupType::Item(state) => {
                     let item = self
                         .player_state
-                        .inventory_state
+                        .player
                         .inventory
                         .iter()
                         .nth(state.index)
@@ -117,8 +171,9 @@ impl RatatuiApp {
 
                     let popup_widget = ItemPopupWidget::new(ItemViewModel::from(
                         item,
-                        &self.player_state.stats_state.stats,
-                    ));
+                        &self.player_state.player,
+                    ))
+                    .confirm_sell(state.confirm_sell);
                     frame.render_widget(Clear, area);
                     frame.render_widget(popup_widget, area);
                 }
@@ -127,17 +182,88 @@ impl RatatuiApp {
     }
 
     fn forward_input(&mut self, key: KeyEvent) -> InputEvent {
+        // Any other key calls off a sale waiting to be confirmed.
+        if let Some(PopupType::Item(state)) = &mut self.popup
+            && key.code != KeyCode::Char('g')
+        {
+            state.confirm_sell = false;
+        }
         if let Some(popup) = &mut self.popup {
             return match popup {
+                PopupType::Item(state) if key.code == KeyCode::Char('u') => {
+                    let index = state.index;
+                    self.use_item(index);
+                    InputEvent::Consumed
+                }
+                PopupType::Item(state) if matches!(key.code, KeyCode::Char('1'..='9')) => {
+                    let index = state.index;
+                    if let KeyCode::Char(c) = key.code {
+                        self.apply_currency(index, c as usize - '1' as usize);
+                    }
+                    InputEvent::Consumed
+                }
+                PopupType::Item(state) if key.code == KeyCode::Char('r') => {
+                    let index = state.index;
+                    if let Some(id) = self.player_state.player.inventory.iter().nth(index).map(|x| x.id) {
+                        self.player_state.player.repair(id);
+                        self.player_state.refresh();
+                    }
+                    InputEvent::Consumed
+                }
+                PopupType::Item(state) if key.code == KeyCode::Char('e') => {
+                    let index = state.index;
+                    if let Some(id) = self.player_state.player.inventory.iter().nth(index).map(|x| x.id)
+                        && self.player_state.player.equip_from_inventory(id)
+                    {
+                        self.popup = None;
+                    }
+                    self.player_state.refresh();
+                    InputEvent::Consumed
+                }
+                // Selling asks for a second press, as it cannot be undone.
+                PopupType::Item(state) if key.code == KeyCode::Char('g') && state.confirm_sell => {
+                    let index = state.index;
+                    if let Some(id) = self.player_state.player.inventory.iter().nth(index).map(|x| x.id)
+                        && self.vendor_state.sell(&mut self.player_state.player, id)
+                    {
+                        self.popup = None;
+                    }
+                    self.player_state.refresh();
+                    InputEvent::Consumed
+                }
                 PopupType::Item(state) => state.handle_key_event(key),
             };
         }
 
         let input = match self.focus {
-            Screen::Stats => InputEvent::Ignored,
+            Screen::Stats => self.player_state.handle_stats_key(key),
             Screen::World => InputEvent::Ignored,
-            Screen::Equipment => self.player_state.equipment_state.handle_key_event(key),
-            Screen::Inventory => self.player_state.inventory_state.handle_key_event(key),
+            Screen::Equipment => self.player_state.handle_equipment_key(key),
+            Screen::Inventory => match key.code {
+                KeyCode::Char('t') if !self.player_state.inventory_state.search.editing => {
+                    if let Some(id) = self.player_state.selected_item().map(|x| x.id) {
+                        self.stash_state.deposit(&mut self.player_state.player, id);
+                        self.player_state.refresh();
+                    }
+                    InputEvent::Consumed
+                }
+                _ => self.player_state.handle_inventory_key(key),
+            },
+            Screen::Stash => {
+                let input = self.stash_state.handle_stash_key(&mut self.player_state.player, key);
+                self.player_state.refresh();
+                input
+            }
+            Screen::Vendor => {
+                let input = self.vendor_state.handle_vendor_key(&mut self.player_state.player, key);
+                self.player_state.refresh();
+                input
+            }
+            Screen::Passives => {
+                let input = self.passive_state.handle_passive_key(&mut self.player_state.player, key);
+                self.player_state.refresh();
+                input
+            }
         };
 
         match input {
@@ -163,6 +289,9 @@ impl RatatuiApp {
             Screen::World => Box::new(&mut self.world_state),
             Screen::Equipment => Box::new(&mut self.player_state.equipment_state),
             Screen::Inventory => Box::new(&mut self.player_state.inventory_state),
+            Screen::Stash => Box::new(&mut self.stash_state),
+            Screen::Passives => Box::new(&mut self.passive_state),
+            Screen::Vendor => Box::new(&mut self.vendor_state),
         }
     }
 
@@ -174,7 +303,43 @@ impl RatatuiApp {
             Screen::Inventory => {
                 self.popup = Some(PopupType::Item(ItemPopupState::new(index)));
             }
+            Screen::Stash => {}
+            Screen::Passives => {}
+            Screen::Vendor => {}
+        }
+    }
+
+    /// Applies the `choice`th currency offered in the popup to the item at `index`.
+    fn apply_currency(&mut self, index: usize, choice: usize) {
+        let player = &mut self.player_state.player;
+        let Some(item) = player.inventory.iter().nth(index) else {
+            return;
+        };
+        let Some(currency) = player.currencies_for(item).get(choice).map(|x| x.id) else {
+            return;
+        };
+
+        let id = item.id;
+        player.apply_currency(currency, id);
+        // Using up a currency stack can move the item to another index.
+        if let Some(index) = player.inventory.iter().position(|x| x.id == id) {
+            self.popup = Some(PopupType::Item(ItemPopupState::new(index)));
+        }
+        self.player_state.refresh();
+    }
+
+    /// Uses the consumable at `index`, closing its popup once the item is used up.
+    fn use_item(&mut self, index: usize) {
+        let player = &mut self.player_state.player;
+        let Some(id) = player.inventory.iter().nth(index).map(|x| x.id) else {
+            return;
+        };
+
+        player.use_item(id);
+        if player.inventory.iter().nth(index).map(|x| x.id) != Some(id) {
+            self.popup = None;
         }
+        self.player_state.refresh();
     }
 }
 
@@ -220,6 +385,18 @@ impl InputHandler for RatatuiApp {
                         self.change_screen(Screen::Equipment);
                         InputEvent::Consumed
                     }
+                    KeyCode::Char('b') => {
+                        self.change_screen(Screen::Stash);
+                        InputEvent::Consumed
+                    }
+                    KeyCode::Char('p') => {
+                        self.change_screen(Screen::Passives);
+                        InputEvent::Consumed
+                    }
+                    KeyCode::Char('v') => {
+                        self.change_screen(Screen::Vendor);
+                        InputEvent::Consumed
+                    }
                     KeyCode::Tab => {
                         let mut iter = Screen::iter();
                         loop {
@@ -249,3 +426,79 @@ impl InputHandler for RatatuiApp {
         input
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::character_class::CharacterClass;
+    use crate::model::equipment::EquipmentSlot;
+    use crossterm::event::KeyModifiers;
+
+    fn app() -> RatatuiApp {
+        let player = Player::new(CharacterClass::find("Warrior").unwrap());
+        RatatuiApp::new(
+            PlayerState::from(player),
+            Stash::with_tabs(&["Gear"]),
+            Vendor::new("Trader", Vec::new(), 1),
+            LootFilter::default(),
+        )
+    }
+
+    fn press(app: &mut RatatuiApp, c: char) {
+        app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+
+    fn quantities(app: &RatatuiApp) -> Vec<u32> {
+        app.player().inventory.iter().map(|x| x.quantity).collect()
+    }
+
+    #[test]
+    fn x_splits_the_selected_stack_instead_of_selling_it() {
+        let mut app = app();
+        press(&mut app, 'i');
+        app.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        let gold = app.player().gold;
+
+        press(&mut app, 'x');
+        assert_eq!(quantities(&app), [2, 1]);
+        assert_eq!(app.player().gold, gold);
+    }
+
+    #[test]
+    fn selling_from_the_item_popup_needs_confirming() {
+        let mut app = app();
+        press(&mut app, 'i');
+        app.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(app.popup.is_some());
+
+        press(&mut app, 'g');
+        press(&mut app, 'r');
+        press(&mut app, 'g');
+        assert_eq!(quantities(&app), [3]);
+
+        press(&mut app, 'g');
+        assert!(app.player().inventory.is_empty());
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn equipment_comes_off_and_goes_back_on() {
+        let mut app = app();
+        press(&mut app, 'e');
+        for _ in 0..3 {
+            app.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        }
+        press(&mut app, 'u');
+        assert!(app.player().equipped_in(EquipmentSlot::Weapon).is_none());
+        assert_eq!(quantities(&app), [3, 1]);
+
+        press(&mut app, 'i');
+        app.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        press(&mut app, 'e');
+        assert!(app.popup.is_none());
+        assert_eq!(app.player().equipped_in(EquipmentSlot::Weapon).map(|x| x.item_base.as_str()), Some("Hand Axe"));
+        assert_eq!(quantities(&app), [3]);
+    }
+}
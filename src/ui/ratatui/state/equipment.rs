@@ -1,13 +1,11 @@
 use crate::input::input_handler::{InputEvent, InputHandler};
-use crate::model::item::Item;
+use crate::model::equipment::EquipmentSlot;
 use crossterm::event::{KeyCode, KeyEvent};
-use std::{collections::HashMap, fmt::Display};
-use strum::{EnumIter, IntoEnumIterator};
+use strum::IntoEnumIterator;
 use crate::ui::focusable::Focusable;
 use crate::ui::ratatui::state::ui::UIState;
 
 pub struct EquipmentState {
-    pub equipment: HashMap<EquipmentSlot, Option<Item>>,
     pub selected: Option<EquipmentSlot>,
     pub ui_state: UIState,
 }
@@ -15,16 +13,6 @@ pub struct EquipmentState {
 impl EquipmentState {
     pub fn new() -> Self {
         Self {
-            equipment: HashMap::from([
-                (EquipmentSlot::Helmet, None),
-                (EquipmentSlot::Amulet, None),
-                (EquipmentSlot::Ring, None),
-                (EquipmentSlot::Weapon, None),
-                (EquipmentSlot::Armour, None),
-                (EquipmentSlot::Belt, None),
-                (EquipmentSlot::Gloves, None),
-                (EquipmentSlot::Boots, None),
-            ]),
             selected: None,
             ui_state: UIState {
                 focused: false
@@ -33,33 +21,6 @@ impl EquipmentState {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone, EnumIter)]
-pub enum EquipmentSlot {
-    Helmet,
-    Ring,
-    Amulet,
-    Weapon,
-    Armour,
-    Belt,
-    Gloves,
-    Boots,
-}
-
-impl Display for EquipmentSlot {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            EquipmentSlot::Helmet => write!(f, "Helmet"),
-            EquipmentSlot::Amulet => write!(f, "Amulet"),
-            EquipmentSlot::Ring => write!(f, "Ring"),
-            EquipmentSlot::Weapon => write!(f, "Weapon"),
-            EquipmentSlot::Armour => write!(f, "Armour"),
-            EquipmentSlot::Belt => write!(f, "Belt"),
-            EquipmentSlot::Gloves => write!(f, "Gloves"),
-            EquipmentSlot::Boots => write!(f, "Boots"),
-        }
-    }
-}
-
 impl EquipmentState {
 
     fn select_next_equipment_slot(&mut self) {
@@ -1,32 +1,139 @@
 use crate::input::input_handler::{InputEvent, InputHandler};
-use crate::model::inventory::Inventory;
 use crate::ui::focusable::Focusable;
 use crate::ui::ratatui::state::ui::UIState;
+use crate::model::item::{Item, ItemClass, ItemRarity};
+use crate::model::item_query::ItemQuery;
+use crate::model::item_sort::SortKey;
+use crate::model::stat::StatType;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::widgets::ListState;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+const SORT_KEYS: [SortKey; 6] = [
+    SortKey::Rarity,
+    SortKey::Class,
+    SortKey::Name,
+    SortKey::ItemLevel,
+    SortKey::Stat(StatType::MaximumDamage),
+    SortKey::Value,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumIter, Display)]
+pub enum InventoryFilter {
+    All,
+    Equipment,
+    Currency,
+    #[strum(to_string = "Rare+")]
+    RareOrBetter,
+}
+
+impl InventoryFilter {
+    pub fn matches(&self, item: &Item) -> bool {
+        match self {
+            InventoryFilter::All => true,
+            InventoryFilter::Equipment => matches!(item.item_class, ItemClass::Equipment(_)),
+            InventoryFilter::Currency => matches!(item.item_class, ItemClass::Currency | ItemClass::Material),
+            InventoryFilter::RareOrBetter => item.rarity >= ItemRarity::Rare,
+        }
+    }
+
+    fn next(&self) -> InventoryFilter {
+        let mut iter = InventoryFilter::iter().skip_while(|x| x != self);
+        iter.nth(1).unwrap_or(InventoryFilter::All)
+    }
+}
+
+#[derive(Default)]
+pub struct SearchState {
+    pub input: String,
+    pub editing: bool,
+    pub query: Option<ItemQuery>,
+    pub error: Option<String>,
+}
+
+impl SearchState {
+    pub fn matches(&self, item: &Item) -> bool {
+        self.query.as_ref().is_none_or(|query| query.matches(item))
+    }
+
+    fn submit(&mut self) {
+        self.editing = false;
+        if self.input.trim().is_empty() {
+            self.query = None;
+            self.error = None;
+            return;
+        }
+
+        match self.input.parse::<ItemQuery>() {
+            Ok(query) => {
+                self.query = Some(query);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> InputEvent {
+        match key.code {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Esc => self.editing = false,
+            _ => {}
+        }
+        InputEvent::Consumed
+    }
+}
 
 pub struct InventoryState {
-    pub inventory: Inventory,
     pub state: ListState,
     pub ui_state: UIState,
+    pub sort_key: Option<SortKey>,
+    pub filter: InventoryFilter,
+    pub search: SearchState,
+    item_count: usize,
 }
 
 impl InventoryState {
-    pub fn new(inventory: Inventory) -> Self {
+    pub fn new(item_count: usize) -> Self {
         Self {
-            inventory,
             state: ListState::default(),
             ui_state: UIState { focused: false },
+            sort_key: None,
+            filter: InventoryFilter::All,
+            search: SearchState::default(),
+            item_count,
+        }
+    }
+
+    pub fn next_sort_key(&mut self) -> SortKey {
+        let index = self
+            .sort_key
+            .and_then(|key| SORT_KEYS.iter().position(|x| *x == key))
+            .map(|i| (i + 1) % SORT_KEYS.len())
+            .unwrap_or(0);
+        self.sort_key = Some(SORT_KEYS[index]);
+        SORT_KEYS[index]
+    }
+
+    pub fn set_item_count(&mut self, item_count: usize) {
+        self.item_count = item_count;
+        match self.state.selected() {
+            Some(_) if item_count == 0 => self.state.select(None),
+            Some(index) if index >= item_count => self.state.select(Some(item_count - 1)),
+            _ => {}
         }
     }
 }
 
 impl InventoryState {
     fn select_next_item(&mut self) {
-        if self.select_if_none() {
+        if self.item_count == 0 || self.select_if_none() {
             return;
         }
-        if self.state.selected().unwrap() >= self.inventory.iter().len() - 1 {
+        if self.state.selected().unwrap() >= self.item_count - 1 {
             self.state.select(Some(0))
         } else {
             self.state.select_next()
@@ -34,11 +141,11 @@ impl InventoryState {
     }
 
     fn select_previous_item(&mut self) {
-        if self.select_if_none() {
+        if self.item_count == 0 || self.select_if_none() {
             return;
         }
-        if self.state.selected().unwrap() <= 0 {
-            self.state.select(Some(self.inventory.iter().len() - 1))
+        if self.state.selected().unwrap() == 0 {
+            self.state.select(Some(self.item_count - 1))
         } else {
             self.state.select_previous()
         };
@@ -60,9 +167,15 @@ impl InventoryState {
 
 impl InputHandler for InventoryState {
     fn handle_key_event(&mut self, key: KeyEvent) -> InputEvent {
+        if self.search.editing {
+            return self.search.handle_key_event(key);
+        }
+
         match key.code {
+            KeyCode::Char('/') => { self.search.editing = true; InputEvent::Consumed },
             KeyCode::Up => { self.select_previous_item(); InputEvent::Consumed },
             KeyCode::Down => {self.select_next_item(); InputEvent::Consumed },
+            KeyCode::Char('f') => { self.filter = self.filter.next(); InputEvent::Consumed },
             KeyCode::Enter => {
                 if let Some(index) = self.state.selected() {
                     InputEvent::Selected(index)
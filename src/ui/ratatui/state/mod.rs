@@ -4,4 +4,6 @@ pub mod player;
 pub mod ui;
 pub mod world;
 pub mod stats;
-pub mod popup;
\ No newline at end of file
+pub mod popup;
+pub mod stash;pub mod passive;
+pub mod vendor;
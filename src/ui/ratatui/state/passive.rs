@@ -0,0 +1,70 @@
+use crate::input::input_handler::{InputEvent, InputHandler};
+use crate::model::passive::{PassiveNode, PassiveTree};
+use crate::model::player::Player;
+use crate::ui::focusable::Focusable;
+use crate::ui::ratatui::state::ui::UIState;
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub struct PassiveTreeState {
+    pub selected: usize,
+    pub ui_state: UIState,
+}
+
+impl PassiveTreeState {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            ui_state: UIState { focused: false },
+        }
+    }
+
+    pub fn selected_node(&self) -> Option<&'static PassiveNode> {
+        PassiveTree::standard().nodes().get(self.selected)
+    }
+
+    /// Enter allocates the selected node and r refunds it.
+    pub fn handle_passive_key(&mut self, player: &mut Player, key: KeyEvent) -> InputEvent {
+        let Some(node) = self.selected_node() else {
+            return self.handle_key_event(key);
+        };
+
+        match key.code {
+            KeyCode::Enter => {
+                player.allocate_passive(&node.id);
+                InputEvent::Consumed
+            }
+            KeyCode::Char('r') => {
+                player.refund_passive(&node.id);
+                InputEvent::Consumed
+            }
+            _ => self.handle_key_event(key),
+        }
+    }
+}
+
+impl InputHandler for PassiveTreeState {
+    fn handle_key_event(&mut self, key: KeyEvent) -> InputEvent {
+        let count = PassiveTree::standard().nodes().len();
+        match key.code {
+            KeyCode::Up if count > 0 => {
+                self.selected = (self.selected + count - 1) % count;
+                InputEvent::Consumed
+            }
+            KeyCode::Down if count > 0 => {
+                self.selected = (self.selected + 1) % count;
+                InputEvent::Consumed
+            }
+            _ => InputEvent::Ignored,
+        }
+    }
+}
+
+impl Focusable for PassiveTreeState {
+    fn on_focus_gained(&mut self) {
+        self.ui_state.focused = true;
+    }
+
+    fn on_focus_lost(&mut self) {
+        self.ui_state.focused = false;
+    }
+}
@@ -1,18 +1,133 @@
 use crate::{model::{player::Player}, ui::ratatui::state::{equipment::EquipmentState, inventory::InventoryState}};
+use crate::input::input_handler::{InputEvent, InputHandler};
+use crate::model::experience::ExperienceCurve;
+use crate::model::item::Item;
+use crate::model::stat::StatType;
 use crate::ui::ratatui::state::stats::StatState;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::time::Duration;
 
 pub struct PlayerState {
+    pub player: Player,
     pub stats_state: StatState,
     pub inventory_state: InventoryState,
     pub equipment_state: EquipmentState,
+    pub experience_curve: ExperienceCurve,
 }
 
 impl PlayerState {
     fn new(player: Player) -> Self {
         Self {
-            stats_state: StatState::from(player.base_stats),
-            inventory_state: InventoryState::new(player.inventory),
+            stats_state: StatState::from(player.get_derived_stats()),
+            inventory_state: InventoryState::new(player.inventory.len()),
             equipment_state: EquipmentState::new(),
+            experience_curve: ExperienceCurve::default(),
+            player,
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.stats_state.stats = self.player.get_derived_stats();
+        self.stats_state.effects = self.player.effects.clone();
+        self.stats_state.experience = (
+            self.player.experience,
+            self.experience_curve.required_for(self.player.level() + 1),
+        );
+        self.stats_state.attribute_points = self.player.attribute_points;
+        self.inventory_state.set_item_count(self.player.inventory.len());
+    }
+
+    pub fn tick(&mut self, elapsed: Duration) {
+        self.player.tick(elapsed);
+        self.refresh();
+    }
+
+    /// Spends attribute points from the Stats screen: 1 for Strength, 2 for Dexterity and
+    /// 3 for Intelligence.
+    pub fn handle_stats_key(&mut self, key: KeyEvent) -> InputEvent {
+        let stat_type = match key.code {
+            KeyCode::Char('1') => StatType::Strength,
+            KeyCode::Char('2') => StatType::Dexterity,
+            KeyCode::Char('3') => StatType::Intelligence,
+            _ => return InputEvent::Ignored,
+        };
+        if self.player.spend_attribute_point(stat_type) {
+            self.refresh();
+        }
+        InputEvent::Consumed
+    }
+
+    pub fn selected_item(&self) -> Option<&Item> {
+        let index = self.inventory_state.state.selected()?;
+        self.player.inventory.iter().nth(index)
+    }
+
+    /// Repairs the item in the selected slot with r and takes it off with u, otherwise
+    /// moves the selection.
+    pub fn handle_equipment_key(&mut self, key: KeyEvent) -> InputEvent {
+        let Some(slot) = self.equipment_state.selected else {
+            return self.equipment_state.handle_key_event(key);
+        };
+
+        match key.code {
+            KeyCode::Char('r') => {
+                if let Some(id) = self.player.equipped_in(slot).map(|x| x.id)
+                    && self.player.repair(id)
+                {
+                    self.refresh();
+                }
+                InputEvent::Consumed
+            }
+            KeyCode::Char('u') => {
+                if self.player.unequip(slot) {
+                    self.refresh();
+                }
+                InputEvent::Consumed
+            }
+            _ => self.equipment_state.handle_key_event(key),
+        }
+    }
+
+    pub fn handle_inventory_key(&mut self, key: KeyEvent) -> InputEvent {
+        if self.inventory_state.search.editing {
+            return self.inventory_state.handle_key_event(key);
+        }
+
+        match key.code {
+            KeyCode::Char('a') => {
+                self.player.inventory.auto_arrange();
+                InputEvent::Consumed
+            }
+            KeyCode::Char('o') => {
+                let sort_key = self.inventory_state.next_sort_key();
+                self.player.inventory.sort_by(sort_key);
+                InputEvent::Consumed
+            }
+            KeyCode::Char('x') => {
+                if let Some(item) = self.selected_item() {
+                    let (id, quantity) = (item.id, item.quantity / 2);
+                    self.player.inventory.split(id, quantity);
+                    self.refresh();
+                }
+                InputEvent::Consumed
+            }
+            KeyCode::Char('m') => {
+                if let Some(item) = self.selected_item() {
+                    let target = self
+                        .player
+                        .inventory
+                        .iter()
+                        .find(|x| x.id != item.id && x.can_stack_with(item))
+                        .map(|x| x.id);
+                    if let Some(target) = target {
+                        let source = item.id;
+                        self.player.inventory.merge(source, target);
+                        self.refresh();
+                    }
+                }
+                InputEvent::Consumed
+            }
+            _ => self.inventory_state.handle_key_event(key),
         }
     }
 }
@@ -1,12 +1,14 @@
 use crate::input::input_handler::{InputEvent, InputHandler};
 use crate::ui::focusable::Focusable;
 use crate::ui::ratatui::state::ui::UIState;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 
 
 pub struct ItemPopupState {
     ui_state: UIState,
     pub index: usize,
+    /// Set by a first press of the sell key, so the next press sells the item.
+    pub confirm_sell: bool,
 }
 
 impl ItemPopupState {
@@ -15,7 +17,8 @@ impl ItemPopupState {
             ui_state: UIState {
                 focused: false
             },
-            index
+            index,
+            confirm_sell: false,
         }
     }
 }
@@ -23,6 +26,10 @@ impl ItemPopupState {
 impl InputHandler for ItemPopupState {
     fn handle_key_event(&mut self, key: KeyEvent) -> InputEvent {
         match key.code {
+            KeyCode::Char('g') => {
+                self.confirm_sell = true;
+                InputEvent::Consumed
+            }
             _ => InputEvent::Ignored,
         }
     }
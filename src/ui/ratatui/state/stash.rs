@@ -0,0 +1,118 @@
+use crate::input::input_handler::{InputEvent, InputHandler};
+use crate::model::item::Item;
+use crate::model::player::Player;
+use crate::model::stash::Stash;
+use crate::ui::focusable::Focusable;
+use crate::ui::ratatui::state::inventory::InventoryState;
+use crossterm::event::{KeyCode, KeyEvent};
+use uuid::Uuid;
+
+pub struct StashState {
+    pub stash: Stash,
+    pub tab: usize,
+    pub inventory_state: InventoryState,
+}
+
+impl StashState {
+    pub fn new(stash: Stash) -> Self {
+        let item_count = stash.tab(0).map(|x| x.items.len()).unwrap_or(0);
+        Self {
+            stash,
+            tab: 0,
+            inventory_state: InventoryState::new(item_count),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        let item_count = self.stash.tab(self.tab).map(|x| x.items.len()).unwrap_or(0);
+        self.inventory_state.set_item_count(item_count);
+    }
+
+    fn selected_item(&self) -> Option<&Item> {
+        let index = self.inventory_state.state.selected()?;
+        self.stash.tab(self.tab)?.items.iter().nth(index)
+    }
+
+    /// Moves an item from the player's inventory into the current tab.
+    pub fn deposit(&mut self, player: &mut Player, id: Uuid) -> bool {
+        let deposited = self.stash.deposit(player, self.tab, id);
+        self.refresh();
+        deposited
+    }
+
+    fn select_tab(&mut self, tab: usize) {
+        self.tab = tab;
+        self.inventory_state.state.select(None);
+        self.refresh();
+        if self.stash.tab(tab).is_some_and(|x| !x.items.is_empty()) {
+            self.inventory_state.state.select_first();
+        }
+    }
+
+    /// Handles the keys that need the player as well as the stash, leaving
+    /// tab and item navigation to [`InputHandler::handle_key_event`].
+    pub fn handle_stash_key(&mut self, player: &mut Player, key: KeyEvent) -> InputEvent {
+        if self.inventory_state.search.editing {
+            return self.handle_key_event(key);
+        }
+
+        match key.code {
+            KeyCode::Char('t') => {
+                if let Some(id) = self.selected_item().map(|x| x.id) {
+                    self.stash.withdraw(player, self.tab, id);
+                    self.refresh();
+                }
+                InputEvent::Consumed
+            }
+            _ => self.handle_key_event(key),
+        }
+    }
+}
+
+impl InputHandler for StashState {
+    fn handle_key_event(&mut self, key: KeyEvent) -> InputEvent {
+        if self.inventory_state.search.editing {
+            return self.inventory_state.handle_key_event(key);
+        }
+
+        match key.code {
+            KeyCode::Left if !self.stash.is_empty() => {
+                self.select_tab((self.tab + self.stash.len() - 1) % self.stash.len());
+                InputEvent::Consumed
+            }
+            KeyCode::Right if !self.stash.is_empty() => {
+                self.select_tab((self.tab + 1) % self.stash.len());
+                InputEvent::Consumed
+            }
+            KeyCode::Char('n') => {
+                let tab = self.stash.add_tab((self.stash.len() + 1).to_string());
+                self.select_tab(tab);
+                InputEvent::Consumed
+            }
+            KeyCode::Char('d') => {
+                if self.stash.remove_tab(self.tab).is_some() {
+                    self.select_tab(self.tab.min(self.stash.len().saturating_sub(1)));
+                }
+                InputEvent::Consumed
+            }
+            KeyCode::Char('m') if self.stash.len() > 1 => {
+                if let Some(id) = self.selected_item().map(|x| x.id) {
+                    self.stash.move_item(self.tab, (self.tab + 1) % self.stash.len(), id);
+                    self.refresh();
+                }
+                InputEvent::Consumed
+            }
+            _ => self.inventory_state.handle_key_event(key),
+        }
+    }
+}
+
+impl Focusable for StashState {
+    fn on_focus_gained(&mut self) {
+        self.inventory_state.on_focus_gained();
+    }
+
+    fn on_focus_lost(&mut self) {
+        self.inventory_state.on_focus_lost();
+    }
+}
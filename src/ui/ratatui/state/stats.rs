@@ -1,9 +1,14 @@
+use crate::model::buff::ActiveEffect;
 use crate::model::stat::StatBlock;
 use crate::ui::focusable::Focusable;
 use crate::ui::ratatui::state::ui::UIState;
 
 pub struct StatState {
     pub stats: StatBlock,
+    pub effects: Vec<ActiveEffect>,
+    /// Experience earned and the total needed for the next level.
+    pub experience: (u64, u64),
+    pub attribute_points: u32,
     pub ui_state: UIState
 }
 
@@ -11,6 +16,9 @@ impl StatState {
     fn new(stats: StatBlock) -> Self {
         Self {
             stats,
+            effects: Vec::new(),
+            experience: (0, 0),
+            attribute_points: 0,
             ui_state: UIState {
                 focused: false,
             }
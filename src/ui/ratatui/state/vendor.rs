@@ -0,0 +1,82 @@
+use crate::input::input_handler::{InputEvent, InputHandler};
+use crate::model::item::Item;
+use crate::model::player::Player;
+use crate::model::vendor::Vendor;
+use crate::ui::focusable::Focusable;
+use crate::ui::ratatui::state::inventory::InventoryState;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::time::Duration;
+use uuid::Uuid;
+
+pub struct VendorState {
+    pub vendor: Vendor,
+    pub inventory_state: InventoryState,
+}
+
+impl VendorState {
+    pub fn new(vendor: Vendor) -> Self {
+        let item_count = vendor.stock.len();
+        Self {
+            vendor,
+            inventory_state: InventoryState::new(item_count),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.inventory_state.set_item_count(self.vendor.stock.len());
+    }
+
+    pub fn selected_item(&self) -> Option<&Item> {
+        let index = self.inventory_state.state.selected()?;
+        self.vendor.stock.iter().nth(index)
+    }
+
+    pub fn tick(&mut self, elapsed: Duration) {
+        if self.vendor.tick(elapsed) {
+            self.inventory_state.state.select(None);
+            self.refresh();
+        }
+    }
+
+    /// Sells an item from the player's inventory to the vendor.
+    pub fn sell(&mut self, player: &mut Player, id: Uuid) -> bool {
+        let sold = self.vendor.sell(player, id);
+        self.refresh();
+        sold
+    }
+
+    /// Handles the keys that need the player as well as the vendor, leaving item
+    /// navigation to [`InputHandler::handle_key_event`].
+    pub fn handle_vendor_key(&mut self, player: &mut Player, key: KeyEvent) -> InputEvent {
+        if self.inventory_state.search.editing {
+            return self.handle_key_event(key);
+        }
+
+        match key.code {
+            KeyCode::Char('y') => {
+                if let Some(id) = self.selected_item().map(|x| x.id) {
+                    self.vendor.buy(player, id);
+                    self.refresh();
+                }
+                InputEvent::Consumed
+            }
+            _ => self.handle_key_event(key),
+        }
+    }
+}
+
+impl InputHandler for VendorState {
+    fn handle_key_event(&mut self, key: KeyEvent) -> InputEvent {
+        self.inventory_state.handle_key_event(key)
+    }
+}
+
+impl Focusable for VendorState {
+    fn on_focus_gained(&mut self) {
+        self.inventory_state.on_focus_gained();
+    }
+
+    fn on_focus_lost(&mut self) {
+        self.inventory_state.on_focus_lost();
+    }
+}
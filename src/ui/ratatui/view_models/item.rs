@@ -1,20 +1,43 @@
+use crate::model::comparison::{RequirementChange, StatDelta};
 use crate::model::item::{EquipmentType, Item, ItemClass, ItemRarity};
+use crate::model::player::Player;
 use crate::model::requirement::StatRequirement;
-use crate::model::stat::{StatBlock, StatType};
+use crate::model::stat::StatType;
+use crate::model::vendor;
 
 pub struct ItemViewModel {
     pub item_base: String,
     pub name: Option<String>,
     pub rarity: ItemRarity,
+    pub identified: bool,
 
+    pub stack_size: Option<(u32, u32)>,
+    pub charges: Option<(u32, u32)>,
+    pub durability: Option<(u32, u32)>,
+    pub use_effect: Option<String>,
+    pub quality: u32,
+    /// "One Hand" or "Two Hand" for weapons.
+    pub handedness: Option<&'static str>,
     pub damage: Option<DamageLine>,
+    /// Attack speed, critical chance and range of a weapon.
+    pub attack: Vec<PropertyLine>,
+    pub defences: Vec<PropertyLine>,
     pub requirements: Vec<RequirementLine>,
     pub item_class: String,
+    pub implicits: Vec<String>,
     pub modifiers: Vec<String>,
+    pub comparison_title: Option<String>,
+    pub stat_deltas: Vec<StatDelta>,
+    pub requirement_changes: Vec<RequirementChange>,
+    pub sell_price: u32,
+    pub repair_cost: u32,
+    /// Carried currencies that can be applied to the item, in the order of their keys.
+    pub currencies: Vec<String>,
 }
 
 impl ItemViewModel {
-    pub fn from(item: &Item, player_stats: &StatBlock) -> ItemViewModel {
+    pub fn from(item: &Item, player: &Player) -> ItemViewModel {
+        let player_stats = player.get_derived_stats();
         let derived = item.get_derived_stats();
         let reqs = item.get_derived_requirements();
 
@@ -26,7 +49,7 @@ impl ItemViewModel {
                     Some(DamageLine {
                         min: derived.get(StatType::MinimumDamage).unwrap().value,
                         max: derived.get(StatType::MaximumDamage).unwrap().value,
-                        is_modified: item.modifiers.iter().any(|m|
+                        is_modified: item.quality > 0 || item.active_modifiers().any(|m|
                             matches!(
                             m.get_affected_stat(),
                             StatType::IncreasedDamage
@@ -39,27 +62,90 @@ impl ItemViewModel {
             _ => None,
         };
 
+        let handedness = item.weapon_type().map(|x| match x.is_two_handed() {
+            true => "Two Hand",
+            false => "One Hand",
+        });
+
+        let attack = [StatType::AttackSpeed, StatType::CriticalChance, StatType::Range]
+            .iter()
+            .filter(|x| item.base_stats.has(**x))
+            .filter_map(|x| derived.get(*x))
+            .map(|stat| PropertyLine {
+                stat_type: stat.stat_type,
+                value: stat.value,
+                is_modified: item.active_modifiers().any(|m| m.get_affected_stat() == stat.stat_type),
+            })
+            .collect();
+
+        let defences = match item.item_class {
+            ItemClass::Equipment(EquipmentType::Armour(_)) => StatType::DEFENCES
+                .iter()
+                .filter(|x| item.base_stats.has(**x))
+                .filter_map(|x| derived.get(*x))
+                .map(|stat| {
+                    let raised_by_quality = item.quality > 0 && item.quality_stats().contains(&stat.stat_type);
+                    PropertyLine {
+                        stat_type: stat.stat_type,
+                        value: stat.value,
+                        is_modified: raised_by_quality
+                            || item.active_modifiers().any(|m| {
+                                m.get_affected_stat() == stat.stat_type
+                                    || (m.get_affected_stat() == StatType::IncreasedDefences
+                                        && stat.stat_type != StatType::BlockChance)
+                            }),
+                    }
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
         let requirements = reqs.requirements.into_iter().map(|r| {
-            let met = player_stats
-                .get(r.stat_type)
-                .map(|s| r.is_met(s))
-                .unwrap_or(false);
+            let met = r.is_met_by(&player_stats);
 
             RequirementLine {
                 requirement: r,
                 is_met: met,
-                is_modified: item.modifiers.iter().any(|m| m.get_affected_stat() == StatType::Requirements)
+                is_modified: item.active_modifiers().any(|m| m.get_affected_stat() == StatType::Requirements)
             }
         }).collect();
 
+        let (comparison_title, stat_deltas, requirement_changes) = match player.compare_equip(item) {
+            Some(comparison) => (
+                Some(match comparison.replaced.is_empty() {
+                    false => format!("Replacing {}:", comparison.replaced.join(" and ")),
+                    true => format!("Equipping as {}:", comparison.slot),
+                }),
+                comparison.changed_stats().cloned().collect(),
+                comparison.requirements,
+            ),
+            None => (None, Vec::new(), Vec::new()),
+        };
+
         ItemViewModel {
-            name: item.name.clone(),
+            name: item.name.clone().filter(|_| item.identified),
             item_base: item.item_base.clone(),
             rarity: item.rarity,
+            identified: item.identified,
+            stack_size: (item.max_stack() > 1).then_some((item.quantity, item.max_stack())),
+            charges: item.charges.zip(item.max_charges()),
+            durability: item.durability.zip(item.max_durability()),
+            use_effect: item.use_effect().map(|x| x.to_string()),
             item_class: item.item_class.to_string(),
+            quality: item.quality,
+            handedness,
             damage,
+            attack,
+            defences,
             requirements,
-            modifiers: item.modifiers.iter().map(|m| m.description()).collect(),
+            implicits: item.implicits.iter().map(|m| m.description()).collect(),
+            modifiers: item.known_modifiers().iter().map(|m| m.description()).collect(),
+            comparison_title,
+            stat_deltas,
+            requirement_changes,
+            sell_price: vendor::sell_price(item),
+            repair_cost: vendor::repair_cost(item),
+            currencies: player.currencies_for(item).iter().map(|x| x.item_base.clone()).collect(),
         }
     }
 }
@@ -72,6 +158,12 @@ pub struct DamageLine {
     pub is_modified: bool,
 }
 
+pub struct PropertyLine {
+    pub stat_type: StatType,
+    pub value: i32,
+    pub is_modified: bool,
+}
+
 pub struct RequirementLine {
     pub requirement: StatRequirement,
     pub is_met: bool,
@@ -1,3 +1,4 @@
+use crate::model::buff::ActiveEffect;
 use crate::model::stat::Stat;
 
 pub struct StatViewModel {
@@ -7,7 +8,29 @@ pub struct StatViewModel {
 impl From<&Stat> for StatViewModel {
     fn from(value: &Stat) -> Self {
         Self {
-            value: format!("{}: {}", value.stat_type.to_string(), value.value),
+            value: format!("{}: {}", value.stat_type.to_string(), value.stat_type.format_value(value.value)),
         }
     }
-}
\ No newline at end of file
+}
+
+pub struct EffectViewModel {
+    pub value: String,
+    pub is_debuff: bool,
+}
+
+impl From<&ActiveEffect> for EffectViewModel {
+    fn from(value: &ActiveEffect) -> Self {
+        let stacks = if value.stacks > 1 { format!(" x{}", value.stacks) } else { String::new() };
+        Self {
+            value: format!(
+                "{}{} ({:+} {}) {}s",
+                value.buff.name,
+                stacks,
+                value.value(),
+                value.buff.stat,
+                value.remaining.as_secs_f32().ceil()
+            ),
+            is_debuff: value.buff.is_debuff(),
+        }
+    }
+}
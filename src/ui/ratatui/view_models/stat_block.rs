@@ -1,17 +1,43 @@
+use crate::model::stat::{Stat, StatType};
 use crate::ui::ratatui::state::stats::StatState;
-use crate::ui::ratatui::view_models::stat::StatViewModel;
+use crate::ui::ratatui::view_models::stat::{EffectViewModel, StatViewModel};
 
 pub struct StatBlockViewModel {
     pub stats: Vec<StatViewModel>,
+    /// Every kind of defence, including those the player has none of.
+    pub defences: Vec<StatViewModel>,
+    pub effects: Vec<EffectViewModel>,
+    pub experience: String,
+    pub attribute_points: Option<String>,
 }
 
 impl From<&StatState> for StatBlockViewModel {
     fn from(value: &StatState) -> Self {
         let mut stats = Vec::new();
-        for stat in value.stats.stats.iter() {
+        for stat in value.stats.stats.iter().filter(|x| !x.stat_type.is_defence()) {
             stats.push(StatViewModel::from(stat));
         }
 
-        Self { stats }
+        let defences = StatType::DEFENCES
+            .iter()
+            .map(|x| value.stats.get(*x).copied().unwrap_or(Stat::new(*x, 0)))
+            .map(|x| StatViewModel::from(&x))
+            .collect();
+
+        let effects = value.effects.iter().map(EffectViewModel::from).collect();
+
+        let (experience, next_level) = value.experience;
+        let attribute_points = match value.attribute_points {
+            0 => None,
+            points => Some(format!("Attribute Points: {}", points)),
+        };
+
+        Self {
+            stats,
+            defences,
+            effects,
+            experience: format!("Experience: {} / {}", experience, next_level),
+            attribute_points,
+        }
     }
 }
\ No newline at end of file
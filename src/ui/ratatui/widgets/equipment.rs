@@ -5,15 +5,21 @@ use ratatui::{
 };
 use ratatui::prelude::{Color, Span, Style};
 use ratatui::widgets::{BorderType};
-use crate::ui::ratatui::state::equipment::{EquipmentSlot, EquipmentState};
+use ratatui::style::Stylize;
+use strum::IntoEnumIterator;
+use crate::model::equipment::EquipmentSlot;
+use crate::model::player::Player;
+use crate::ui::ratatui::state::equipment::EquipmentState;
 
 pub struct PlayerEquipmentWidget<'a> {
+    player: &'a Player,
     equipment_state: &'a EquipmentState,
 }
 
 impl<'a> PlayerEquipmentWidget<'a> {
-    pub fn new(equipment_state: &'a EquipmentState) -> Self {
+    pub fn new(player: &'a Player, equipment_state: &'a EquipmentState) -> Self {
         Self {
+            player,
             equipment_state
         }
     }
@@ -48,28 +54,31 @@ impl<'a> Widget for PlayerEquipmentWidget<'a> {
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(layout[2]);
 
+        let off_hand_belt = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(layout[3]);
+
         let gloves_boots = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(layout[4]);
 
-        for (slot, item) in &self.equipment_state.equipment {
+        for slot in EquipmentSlot::iter() {
             let mut slot_label = vec![Span::from(slot.to_string())];
             if let Some(selected_slot) = &self.equipment_state.selected
-                && slot == selected_slot {
+                && slot == *selected_slot {
                 slot_label.insert(0, Span::from("> "));
                 slot_label.push(Span::from(" <"));
             }
             let slot_name = Line::from(slot_label).centered();
 
-            let item_name = match item {
-                Some(x) => match &x.name {
-                    Some(name) => name,
-                    None => &x.item_base,
-                },
-                None => "- empty -",
-            };
-            let slot_contained = Line::from(item_name).centered();
+            let slot_contained = match self.player.equipped_in(slot) {
+                Some(x) if x.is_broken() => Line::from(format!("{} (broken)", x.display_name())).red(),
+                Some(x) => Line::from(x.display_name()),
+                None => Line::from("- empty -"),
+            }
+            .centered();
 
             let item_block = match slot {
                 EquipmentSlot::Helmet => layout[0],
@@ -77,7 +86,8 @@ impl<'a> Widget for PlayerEquipmentWidget<'a> {
                 EquipmentSlot::Ring => trinket_layout[0],
                 EquipmentSlot::Weapon => body_weapon[0],
                 EquipmentSlot::Armour => body_weapon[1],
-                EquipmentSlot::Belt => layout[3],
+                EquipmentSlot::OffHand => off_hand_belt[0],
+                EquipmentSlot::Belt => off_hand_belt[1],
                 EquipmentSlot::Gloves => gloves_boots[0],
                 EquipmentSlot::Boots => gloves_boots[1],
             };
@@ -85,7 +95,10 @@ impl<'a> Widget for PlayerEquipmentWidget<'a> {
         }
 
         if self.equipment_state.ui_state.focused {
-            main_block = main_block.border_style(Style::default().fg(Color::Cyan)).border_type(BorderType::Double);
+            main_block = main_block
+                .border_style(Style::default().fg(Color::Cyan))
+                .border_type(BorderType::Double)
+                .title_bottom(Line::from("[r] Repair").centered());
         }
 
         main_block.render(area, buf);
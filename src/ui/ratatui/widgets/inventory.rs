@@ -1,65 +1,239 @@
-use crate::model::item::{ItemRarity};
-use crate::ui::ratatui::state::inventory::InventoryState;
+use crate::model::inventory::{Inventory, InventoryGrid};
+use crate::model::loot_filter::{FilterColour, ItemStyle, LootFilter};
+use crate::ui::ratatui::state::inventory::{InventoryFilter, InventoryState};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::prelude::{Line, Style};
-use ratatui::style::{Color, Stylize};
+use ratatui::style::{Color, Modifier, Stylize};
 use ratatui::text::Span;
-use ratatui::widgets::{Block, BorderType, HighlightSpacing, List, ListItem, StatefulWidget, Widget};
+use ratatui::widgets::{Block, BorderType, HighlightSpacing, List, ListItem, Paragraph, StatefulWidget, Widget};
+use uuid::Uuid;
+
+const CELL_WIDTH: u16 = 2;
 
 pub struct PlayerInventoryWidget<'a>{
+    pub inventory: &'a Inventory,
     pub inventory_state: &'a InventoryState,
+    pub loot_filter: &'a LootFilter,
+    title: &'a str,
 }
 
 impl<'a> PlayerInventoryWidget<'a> {
-    pub fn new(inventory_state: &'a InventoryState) -> Self {
+    pub fn new(inventory: &'a Inventory, inventory_state: &'a InventoryState, loot_filter: &'a LootFilter) -> Self {
         Self {
-            inventory_state
+            inventory,
+            inventory_state,
+            loot_filter,
+            title: "Inventory",
+        }
+    }
+
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
+    fn render_grid(&self, grid: &InventoryGrid, area: Rect, buf: &mut Buffer) {
+        let grid_width = grid.width as u16 * CELL_WIDTH;
+        let origin_x = area.x + area.width.saturating_sub(grid_width) / 2;
+
+        for y in 0..grid.height as u16 {
+            for x in 0..grid.width as u16 {
+                set_clipped(buf, area, origin_x + x * CELL_WIDTH, area.y + y, "· ", Style::default().fg(Color::DarkGray));
+            }
+        }
+
+        let filter = self.inventory_state.filter;
+        let search = &self.inventory_state.search;
+        let matching: Vec<Uuid> = self
+            .inventory
+            .filter(move |x| filter.matches(x) && search.matches(x))
+            .map(|x| x.id)
+            .collect();
+
+        for (index, item) in self.inventory.iter().enumerate() {
+            let Some(position) = self.inventory.position_of(item) else {
+                continue;
+            };
+            let (width, height) = item.size();
+            let item_style = self.loot_filter.style_for(item);
+
+            let style = if self.inventory_state.state.selected() == Some(index) {
+                Style::default().fg(Color::Black).bg(Color::Cyan)
+            } else if !matching.contains(&item.id) {
+                Style::default().fg(Color::Gray).bg(Color::DarkGray)
+            } else {
+                filter_style(item_style)
+            };
+
+            let x = origin_x + position.x as u16 * CELL_WIDTH;
+            let y = area.y + position.y as u16;
+            let label_width = width * CELL_WIDTH as usize;
+            for row in 0..height as u16 {
+                let text = if row != 0 || item_style.hidden {
+                    " ".repeat(label_width)
+                } else if item.max_stack() > 1 {
+                    format!("{:>label_width$.label_width$}", item.quantity)
+                } else {
+                    format!("{:<label_width$.label_width$}", item.display_name())
+                };
+                let style = if row + 1 == height as u16 {
+                    style
+                } else {
+                    style.remove_modifier(Modifier::UNDERLINED)
+                };
+                set_clipped(buf, area, x, y + row, &text, style);
+            }
+        }
+
+        let line_area = Rect { y: area.y + grid.height as u16, height: 1, ..area }.intersection(area);
+        if search.editing {
+            Paragraph::new(Line::from(format!("/{}_", search.input))).render(line_area, buf);
+        } else if let Some(error) = &search.error {
+            Paragraph::new(Line::from(error.as_str()).red()).render(line_area, buf);
+        } else if let Some(item) = self.inventory_state.state.selected().and_then(|i| self.inventory.iter().nth(i)) {
+            let name = Line::from(item.display_name()).style(filter_style(self.loot_filter.style_for(item))).centered();
+            Paragraph::new(name).render(line_area, buf);
         }
     }
 }
 
+/// Writes the text at the position, cut off at the edges of `area`, so a grid taller or
+/// wider than the space it is given does not draw over its neighbours or off the buffer.
+fn set_clipped(buf: &mut Buffer, area: Rect, x: u16, y: u16, text: &str, style: Style) {
+    if x < area.left() || x >= area.right() || y < area.top() || y >= area.bottom() {
+        return;
+    }
+    buf.set_stringn(x, y, text, (area.right() - x) as usize, style);
+}
+
+/// Hidden items are drawn dimmed rather than left out so the grid keeps its layout.
+/// Border colours are drawn as an underline along the bottom row of the item.
+fn filter_style(item_style: ItemStyle) -> Style {
+    if item_style.hidden {
+        return Style::default().fg(Color::DarkGray).bg(Color::Black);
+    }
+
+    let colour = |x: FilterColour| Color::Rgb(x.r, x.g, x.b);
+    let mut style = Style::default();
+    if let Some(text_colour) = item_style.text_colour {
+        style = style.fg(colour(text_colour));
+    }
+    if let Some(background_colour) = item_style.background_colour {
+        style = style.bg(colour(background_colour));
+    }
+    if let Some(border_colour) = item_style.border_colour {
+        style = style.underline_color(colour(border_colour)).add_modifier(Modifier::UNDERLINED);
+    }
+    style
+}
+
 impl<'a> Widget for PlayerInventoryWidget<'a>{
     fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
+        let mut block = Block::bordered().title(Line::from(self.title).centered());
+
+        let mut options = Vec::new();
+        if let Some(sort_key) = self.inventory_state.sort_key {
+            options.push(format!("Sort: {}", sort_key));
+        }
+        if self.inventory_state.filter != InventoryFilter::All {
+            options.push(self.inventory_state.filter.to_string());
+        }
+        if self.inventory_state.search.query.is_some() {
+            options.push(format!("/{}", self.inventory_state.search.input));
+        }
+        if !options.is_empty() {
+            block = block.title_bottom(Line::from(options.join(" | ")).centered());
+        }
+
+        if self.inventory_state.ui_state.focused {
+            block = block.border_style(Style::default().fg(Color::Cyan)).border_type(BorderType::Double);
+        }
+
+        if let Some(grid) = self.inventory.grid() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            self.render_grid(grid, inner, buf);
+            return;
+        }
 
         let items: Vec<ListItem> = self
-            .inventory_state
-            .inventory         
+            .inventory
             .iter()
             .map(|item| {
-                let n: Span;
-                if let Some(name) = &item.name {
-                    n = Span::from(format!("{} ({})", name, item.item_base))
-                } else {
-                    n = Span::from(&item.item_base)
-                }
-                let mut l = Line::from(n);
-                l = match item.rarity {
-                    ItemRarity::Magic => l.fg(Color::Indexed(69)),
-                    ItemRarity::Rare => l.fg(Color::Indexed(227)),
-                    ItemRarity::Unique => l.fg(Color::Indexed(208)),
-                    _ => l,
+                let n = match item.display_name() {
+                    name if name != item.item_base => Span::from(format!("{} ({})", name, item.item_base)),
+                    name => Span::from(name),
                 };
-                ListItem::new(l)
+                ListItem::new(Line::from(n).style(filter_style(self.loot_filter.style_for(item))))
             })
             .collect();
 
-        let mut block = Block::bordered().title(Line::from("Inventory").centered());
-
-        if self.inventory_state.ui_state.focused {
-            block = block.border_style(Style::default().fg(Color::Cyan)).border_type(BorderType::Double);
-        }
-
         let list = List::new(items)
             .block(block)
             .highlight_symbol(">")
             .highlight_spacing(HighlightSpacing::WhenSelected);
-        
+
         let mut list_state = self.inventory_state.state;
 
         StatefulWidget::render(list, area, buf, &mut list_state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::item::{Item, ItemRarity};
+
+    #[test]
+    fn grid_is_clipped_to_its_area() {
+        let mut inventory = Inventory::with_grid(10, 8);
+        inventory.add(Item::builder().base(String::from("Claymore")).build().unwrap()).unwrap();
+        let state = InventoryState::new(inventory.len());
+        let filter = LootFilter::default();
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 8));
+        PlayerInventoryWidget::new(&inventory, &state, &filter).render(Rect::new(0, 0, 12, 5), &mut buf);
+        for y in 5..8 {
+            for x in 0..12 {
+                assert_eq!(buf[(x, y)].symbol(), " ", "drew outside the area at ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn grid_larger_than_the_buffer_does_not_panic() {
+        let inventory = Inventory::with_grid(12, 12);
+        let state = InventoryState::new(inventory.len());
+        let filter = LootFilter::default();
+
+        let area = Rect::new(0, 0, 10, 4);
+        let mut buf = Buffer::empty(area);
+        PlayerInventoryWidget::new(&inventory, &state, &filter).render(area, &mut buf);
+    }
+
+    #[test]
+    fn list_hides_the_names_of_unidentified_items() {
+        let mut inventory = Inventory::new();
+        let gloves = Item::builder()
+            .base(String::from("Leather Gloves"))
+            .name(String::from("Dusk Ward"))
+            .rarity(ItemRarity::Rare)
+            .unidentified()
+            .build()
+            .unwrap();
+        inventory.add(gloves).unwrap();
+        let state = InventoryState::new(inventory.len());
+        let filter = LootFilter::default();
+
+        let area = Rect::new(0, 0, 40, 4);
+        let mut buf = Buffer::empty(area);
+        PlayerInventoryWidget::new(&inventory, &state, &filter).render(area, &mut buf);
+        let text: String = buf.content().iter().map(|x| x.symbol()).collect();
+        assert!(text.contains("Leather Gloves"));
+        assert!(!text.contains("Dusk Ward"));
+    }
+}
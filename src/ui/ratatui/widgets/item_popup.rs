@@ -8,11 +8,18 @@ use crate::ui::ratatui::view_models::item::ItemViewModel;
 
 pub struct ItemPopupWidget {
     item: ItemViewModel,
+    confirm_sell: bool,
 }
 
 impl ItemPopupWidget {
     pub fn new(item: ItemViewModel) -> Self {
-        Self { item }
+        Self { item, confirm_sell: false }
+    }
+
+    /// Asks for the sale to be confirmed in place of offering it.
+    pub fn confirm_sell(mut self, confirm_sell: bool) -> Self {
+        self.confirm_sell = confirm_sell;
+        self
     }
 }
 
@@ -56,6 +63,39 @@ impl Widget for ItemPopupWidget {
 
         let mut item_description = Vec::<Line>::new();
 
+        if let Some((quantity, max_stack)) = self.item.stack_size {
+            item_description.push(Line::from(format!("Stack Size: {}/{}", quantity, max_stack)).centered());
+        }
+
+        if let Some((charges, max_charges)) = self.item.charges {
+            item_description.push(Line::from(format!("Charges: {}/{}", charges, max_charges)).centered());
+        }
+
+        if let Some((durability, max_durability)) = self.item.durability {
+            let line = Line::from(format!("Durability: {}/{}", durability, max_durability));
+            item_description.push(match durability {
+                0 => line.red().centered(),
+                _ => line.centered(),
+            });
+            if durability == 0 {
+                item_description.push(Line::from("Broken").red().bold().centered());
+            }
+        }
+
+        if let Some(use_effect) = &self.item.use_effect {
+            item_description.push(Line::from(use_effect.clone()).fg(Color::Indexed(69)).centered());
+        }
+
+        if self.item.quality > 0 {
+            let start = Span::from("Quality: ");
+            let quality = Span::from(format!("+{}%", self.item.quality)).fg(Color::Indexed(69));
+            item_description.push(Line::from(vec![start, quality]).centered());
+        }
+
+        if let Some(handedness) = &self.item.handedness {
+            item_description.push(Line::from(format!("{} ({})", self.item.item_class, handedness)).dark_gray().centered());
+        }
+
         if let Some(dmg) = &self.item.damage {
             let start = Span::from("Damage: ");
             let mut d = Span::from(format!("{} - {}", dmg.min, dmg.max));
@@ -65,6 +105,24 @@ impl Widget for ItemPopupWidget {
             item_description.push(Line::from(vec![start, d]).centered());
         }
 
+        for attack in self.item.attack.iter() {
+            let start = Span::from(format!("{}: ", attack.stat_type));
+            let mut value = Span::from(attack.stat_type.format_value(attack.value));
+            if attack.is_modified {
+                value = value.fg(Color::Indexed(69));
+            }
+            item_description.push(Line::from(vec![start, value]).centered());
+        }
+
+        for defence in self.item.defences.iter() {
+            let start = Span::from(format!("{}: ", defence.stat_type));
+            let mut value = Span::from(defence.stat_type.format_value(defence.value));
+            if defence.is_modified {
+                value = value.fg(Color::Indexed(69));
+            }
+            item_description.push(Line::from(vec![start, value]).centered());
+        }
+
         for requirement in self.item.requirements.iter() {
             let mut p1 = Span::from(format!(
                 "Required {}: ",
@@ -85,6 +143,14 @@ impl Widget for ItemPopupWidget {
             item_description.push(Line::from(vec![p1, amt]).centered());
         }
 
+        for implicit in self.item.implicits.iter() {
+            item_description.push(Line::from(implicit.clone()).fg(Color::Indexed(69)).centered());
+        }
+
+        if !self.item.implicits.is_empty() && !self.item.modifiers.is_empty() {
+            item_description.push(Line::from("────────").dark_gray().centered());
+        }
+
         for modifier in self.item.modifiers.iter() {
             item_description.push(
                 Line::from(modifier.clone())
@@ -93,6 +159,77 @@ impl Widget for ItemPopupWidget {
             );
         }
 
+        if !self.item.identified {
+            item_description.push(Line::from("Unidentified").red().centered());
+        }
+
+        if self.item.sell_price > 0 {
+            item_description.push(Line::from(format!("Sells for {} gold", self.item.sell_price)).dark_gray().centered());
+        }
+
+        if let Some(title) = &self.item.comparison_title {
+            item_description.push(Line::from(""));
+            item_description.push(Line::from(title.clone()).centered());
+        }
+
+        for delta in self.item.stat_deltas.iter() {
+            let start = Span::from(format!(
+                "{}: {} -> {} ",
+                delta.stat_type,
+                delta.stat_type.format_value(delta.before),
+                delta.stat_type.format_value(delta.after)
+            ));
+            let difference = delta.difference();
+            let d = if difference > 0 {
+                Span::from(format!("(+{})", delta.stat_type.format_value(difference))).green()
+            } else {
+                Span::from(format!("({})", delta.stat_type.format_value(difference))).red()
+            };
+            item_description.push(Line::from(vec![start, d]).centered());
+        }
+
+        for change in self.item.requirement_changes.iter() {
+            let line = Line::from(format!(
+                "{}: Required {} {}",
+                change.item_name, change.requirement.stat_type, change.requirement.amount
+            ));
+            item_description.push(if change.is_met {
+                line.green().centered()
+            } else {
+                line.red().centered()
+            });
+        }
+
+        if self.item.comparison_title.is_some() {
+            block = block.title_bottom(Line::from("[e] Equip").centered());
+        }
+
+        if self.item.use_effect.is_some() {
+            block = block.title_bottom(Line::from("[u] Use").centered());
+        }
+
+        if !self.item.currencies.is_empty() {
+            let keys: Vec<String> = self
+                .item
+                .currencies
+                .iter()
+                .enumerate()
+                .map(|(index, name)| format!("[{}] {}", index + 1, name))
+                .collect();
+            block = block.title_bottom(Line::from(keys.join(" ")).centered());
+        }
+
+        if self.item.repair_cost > 0 {
+            block = block.title_bottom(Line::from(format!("[r] Repair ({} gold)", self.item.repair_cost)).centered());
+        }
+
+        block = match self.confirm_sell {
+            true => block.title_bottom(
+                Line::from(format!("Sell for {} gold? [g] Confirm", self.item.sell_price)).yellow().centered(),
+            ),
+            false => block.title_bottom(Line::from("[g] Sell").centered()),
+        };
+
         Paragraph::new(item_description)
             .block(block)
             .render(area, buf);
@@ -2,4 +2,7 @@ pub mod player_stats;
 pub mod inventory;
 pub mod equipment;
 pub mod world;
-pub mod item_popup;
\ No newline at end of file
+pub mod item_popup;
+pub mod status;
+pub mod stash;pub mod passive_tree;
+pub mod vendor;
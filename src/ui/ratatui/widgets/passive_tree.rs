@@ -0,0 +1,86 @@
+use crate::model::passive::PassiveTree;
+use crate::model::player::Player;
+use crate::ui::ratatui::state::passive::PassiveTreeState;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Paragraph, Widget};
+
+pub struct PassiveTreeWidget<'a> {
+    passive_state: &'a PassiveTreeState,
+    player: &'a Player,
+}
+
+impl<'a> PassiveTreeWidget<'a> {
+    pub fn new(passive_state: &'a PassiveTreeState, player: &'a Player) -> Self {
+        Self {
+            passive_state,
+            player,
+        }
+    }
+}
+
+impl<'a> Widget for PassiveTreeWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let tree = PassiveTree::standard();
+        let mut lines = Vec::<Line>::new();
+        for (index, node) in tree.nodes().iter().enumerate() {
+            let is_start = node.start.as_deref() == Some(self.player.class.as_str());
+            let (marker, colour) = if is_start || self.player.passives.contains(&node.id) {
+                ("[x]", Color::Green)
+            } else if tree.can_allocate(&self.player.class, &self.player.passives, &node.id) {
+                ("[+]", Color::Yellow)
+            } else {
+                ("[ ]", Color::DarkGray)
+            };
+
+            let mut spans = vec![
+                Span::from(format!("{} {}", marker, node.name)).fg(colour),
+            ];
+            if let Some(class) = &node.start {
+                spans.push(Span::from(format!(" ({} start)", class)).italic());
+            }
+            let modifiers: Vec<String> = node.modifiers.iter().map(|x| x.description()).collect();
+            if !modifiers.is_empty() {
+                spans.push(Span::from(format!(": {}", modifiers.join(", "))));
+            }
+
+            let mut line = Line::from(spans);
+            if index == self.passive_state.selected && self.passive_state.ui_state.focused {
+                line = line.reversed();
+            }
+            lines.push(line);
+        }
+
+        let selected_links = self
+            .passive_state
+            .selected_node()
+            .map(|node| {
+                let names: Vec<&str> = node.links.iter().filter_map(|x| tree.node(x)).map(|x| x.name.as_str()).collect();
+                format!("Links: {}", names.join(", "))
+            })
+            .unwrap_or_default();
+
+        let title = format!("Passives ({} points)", self.player.passive_points());
+        let mut block = Block::bordered()
+            .title(Line::from(title).centered())
+            .title_bottom(Line::from(selected_links).centered());
+        if self.passive_state.ui_state.focused {
+            block = block
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title_bottom(Line::from("[Enter] Allocate [r] Refund").right_aligned());
+        }
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = (self.passive_state.selected + 1).saturating_sub(visible) as u16;
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll, 0))
+            .render(area, buf);
+    }
+}
@@ -1,6 +1,6 @@
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::Line;
 use ratatui::widgets::{Block, BorderType, Paragraph, Widget};
 use crate::ui::ratatui::state::stats::StatState;
@@ -28,8 +28,28 @@ impl<'a> Widget for PlayerStatsWidget<'a> {
         for stat in view.stats.iter() {
             lines.push(Line::from(stat.value.clone()))
         }
+        lines.push(Line::from(view.experience.clone()));
+        if let Some(attribute_points) = &view.attribute_points {
+            lines.push(Line::from(attribute_points.clone()).fg(Color::Yellow));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("Defences").bold());
+        for defence in view.defences.iter() {
+            lines.push(Line::from(defence.value.clone()));
+        }
+        if !view.effects.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from("Effects").bold());
+            for effect in view.effects.iter() {
+                let colour = if effect.is_debuff { Color::Red } else { Color::Green };
+                lines.push(Line::from(effect.value.clone()).fg(colour));
+            }
+        }
 
         let mut block = Block::bordered().title(Line::from("Stats").centered());
+        if view.attribute_points.is_some() {
+            block = block.title_bottom(Line::from("[1] Str [2] Dex [3] Int").centered());
+        }
 
         if self.stats.ui_state.focused {
             block = block.border_type(BorderType::Double).border_style(Style::default().fg(Color::Cyan))
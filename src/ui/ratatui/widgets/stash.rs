@@ -0,0 +1,51 @@
+use crate::model::loot_filter::LootFilter;
+use crate::ui::ratatui::state::stash::StashState;
+use crate::ui::ratatui::widgets::inventory::PlayerInventoryWidget;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Line;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, BorderType, Paragraph, Tabs, Widget};
+
+pub struct StashWidget<'a> {
+    stash_state: &'a StashState,
+    loot_filter: &'a LootFilter,
+}
+
+impl<'a> StashWidget<'a> {
+    pub fn new(stash_state: &'a StashState, loot_filter: &'a LootFilter) -> Self {
+        Self {
+            stash_state,
+            loot_filter,
+        }
+    }
+}
+
+impl<'a> Widget for StashWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let Some(tab) = self.stash_state.stash.tab(self.stash_state.tab) else {
+            let mut block = Block::bordered().title(Line::from("Stash").centered());
+            if self.stash_state.inventory_state.ui_state.focused {
+                block = block.border_type(BorderType::Double).border_style(Style::default().fg(Color::Cyan));
+            }
+            Paragraph::new(Line::from("No stash tabs, press n to add one").centered())
+                .block(block)
+                .render(area, buf);
+            return;
+        };
+
+        let [tabs_area, tab_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+
+        Tabs::new(self.stash_state.stash.tabs().iter().map(|x| x.name.clone()))
+            .select(self.stash_state.tab)
+            .highlight_style(Style::default().fg(Color::Cyan).bold())
+            .render(tabs_area, buf);
+
+        PlayerInventoryWidget::new(&tab.items, &self.stash_state.inventory_state, self.loot_filter)
+            .title(&tab.name)
+            .render(tab_area, buf);
+    }
+}
@@ -0,0 +1,50 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Line;
+use ratatui::style::Stylize;
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Paragraph, Widget};
+use crate::model::player::Player;
+
+pub struct StatusWidget<'a> {
+    player: &'a Player,
+}
+
+impl<'a> StatusWidget<'a> {
+    pub fn new(player: &'a Player) -> Self {
+        Self {
+            player,
+        }
+    }
+}
+
+impl<'a> Widget for StatusWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let block = Block::bordered().title(Line::from("Status").centered());
+
+        let weight = self.player.inventory.total_weight();
+        let weight_limit = self.player.weight_limit();
+        let mut load = Span::from(format!("{:.1} / {:.1}", weight, weight_limit));
+        if weight > weight_limit {
+            load = load.red();
+        }
+
+        let lines = vec![
+            Line::from(format!("Life: {} / {}", self.player.life, self.player.max_life())),
+            Line::from(vec![Span::from("Load: "), load]),
+            Line::from(format!(
+                "Items: {}{}  Gold: {}",
+                self.player.inventory.len(),
+                self.player.inventory.max_slots.map(|x| format!(" / {}", x)).unwrap_or_default(),
+                self.player.gold
+            )),
+        ];
+
+        Paragraph::new(lines)
+            .block(block)
+            .render(area, buf)
+    }
+}
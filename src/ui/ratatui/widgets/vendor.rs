@@ -0,0 +1,49 @@
+use crate::model::loot_filter::LootFilter;
+use crate::model::vendor;
+use crate::ui::ratatui::state::vendor::VendorState;
+use crate::ui::ratatui::widgets::inventory::PlayerInventoryWidget;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Line;
+use ratatui::widgets::Widget;
+
+pub struct VendorWidget<'a> {
+    vendor_state: &'a VendorState,
+    loot_filter: &'a LootFilter,
+    gold: u32,
+}
+
+impl<'a> VendorWidget<'a> {
+    pub fn new(vendor_state: &'a VendorState, loot_filter: &'a LootFilter, gold: u32) -> Self {
+        Self {
+            vendor_state,
+            loot_filter,
+            gold,
+        }
+    }
+}
+
+impl<'a> Widget for VendorWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let [info_area, stock_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+
+        let price = match self.vendor_state.selected_item() {
+            Some(item) => format!("{}: {} gold [y] Buy", item.display_name(), vendor::buy_price(item)),
+            None => String::from("Sell from the inventory with [x]"),
+        };
+        Line::from(format!(
+            "Gold: {} | {} | Restock in {}s",
+            self.gold,
+            price,
+            self.vendor_state.vendor.restock_in.as_secs()
+        ))
+        .render(info_area, buf);
+
+        PlayerInventoryWidget::new(&self.vendor_state.vendor.stock, &self.vendor_state.inventory_state, self.loot_filter)
+            .title(&self.vendor_state.vendor.name)
+            .render(stock_area, buf);
+    }
+}